@@ -1,40 +1,159 @@
 use macroquad::prelude::*;
 
+use std::collections::HashSet;
+use std::time::Duration;
+
+pub mod alert;
+pub mod audio;
+pub mod boss;
 pub mod bullet;
+pub mod codec;
 pub mod collidermap;
+pub mod drain;
 pub mod effect;
+pub mod enemy;
 pub mod equipmenu;
+pub mod explosion;
+pub mod hud;
+pub mod inventory;
+pub mod itemeffect;
+pub mod laser;
 pub mod map;
+pub mod noise;
+pub mod notification;
+pub mod pathfinder;
+pub mod pickup;
 pub mod player;
+pub mod prisoner;
 pub mod room;
+pub mod roomstate;
+pub mod save;
+pub mod scent;
+pub mod script;
+pub mod securitycamera;
 pub mod settings;
+pub mod spawn;
+pub mod state;
+pub mod status;
+pub mod ui;
+pub mod vision;
 
-use self::bullet::Bullet;
+use self::alert::Alert;
+use self::audio::Audio;
+use self::boss::Boss;
+use self::bullet::{Blast, Bullet, Projectile};
+use self::codec::Codec;
 use self::collidermap::ColliderMap;
+use self::drain::Drain;
 use self::effect::Effect;
-use self::equipmenu::EquipMenu;
+use self::enemy::{Enemy, EnemyKind};
+use self::equipmenu::{EquipMenu, Item, Weapon};
+use self::explosion::Explosion;
+use self::hud::Hud;
+use self::inventory::Inventory;
+use self::laser::Laser;
 use self::map::Map;
-use self::player::Player;
+use self::noise::{Noise, NoiseBus, NoiseKind};
+use self::notification::Notification;
+use self::pathfinder::Route;
+use self::pickup::{Pickup, PickupKind};
+use self::player::{Direction, Player};
+use self::prisoner::Prisoner;
 use self::room::Room;
+use self::roomstate::{Respawn, RoomStates};
+use self::save::{Password, SaveData};
+use self::scent::ScentTrail;
+use self::script::Scripts;
+use self::securitycamera::SecurityCamera;
 use self::settings::Settings;
+use self::spawn::{Spawn, SpawnKind};
+use self::state::{State, StateStack};
+use self::status::{StatusEffects, StatusKind};
+use self::ui::{Anchor, Ui};
+use self::vision::Vision;
 
 pub struct Game {
     settings: Settings,
+    states: StateStack,
+    running: bool,
+    floor: String,
+    // What's in each save slot while they're listed
+    slots: Vec<Option<SaveData>>,
+    typed: String,
+    // Saved, empty slot, wrong password
+    note: Option<String>,
+    // Taken on every new room, Continue goes back to it
+    checkpoint: Option<SaveData>,
     player: Player,
     map: Map,
     current_room: Room,
+    enemies: Vec<Enemy>,
+    cameras: Vec<SecurityCamera>,
+    lasers: Vec<Laser>,
+    bosses: Vec<Boss>,
+    explosions: Vec<Explosion>,
+    pickups: Vec<Pickup>,
+    mines: Vec<Bullet>,
+    prisoners: Vec<Prisoner>,
+    room_states: RoomStates,
+    flags: HashSet<String>,
+    locked: HashSet<String>,
+    codec: Codec,
+    scripts: Scripts,
+    notification: Notification,
+    hud: Hud,
+    alert: Alert,
+    audio: Audio,
+    noise_bus: NoiseBus,
+    scent_trail: ScentTrail,
     camera_position: Vec2,
+    // Where a script has the camera looking, and for how long
+    camera_focus: Option<(Vec2, f32)>,
     time_since_last_check: f32,
     check_interval: f32,
     delta_time: f32,
+    step_timer: f32,
+    // Only the first frame against a wall bumps
+    touching_wall: bool,
+    transmit_timer: f32,
 }
 
+const TITLE: &str = "METAL GEAR RUST";
+const START_FLOOR: &str = "b1_f1";
+const GAME_OVER_TIME: f32 = 3.0;
+// Continues come back with at least this much of his life
+const CONTINUE_HEALTH: f32 = 0.5;
+const PUNCH_DAMAGE: f32 = 1.0;
+const PUNCH_KNOCKBACK: f32 = 640.0;
+const STEP_INTERVAL: f32 = 0.4;
+const BITE_DAMAGE: f32 = 4.0;
+const BITE_POISON: Duration = Duration::from_secs(20);
+const BLAST_STUN: Duration = Duration::from_millis(800);
+const BULLET_DAMAGE: f32 = 1.0;
+const BOSS_SHOT_DAMAGE: f32 = 6.0;
+const GUARD_VISION: Vision = Vision::new(320.0, 0.6);
+// Guards only see through the uniform up close
+const DISGUISE_RANGE: f32 = 128.0;
+const TRANSMIT_INTERVAL: f32 = 5.0;
+const COMPASS_DISTANCE: f32 = 96.0;
+const COMPASS_SIZE: f32 = 16.0;
+
 impl Game {
     pub async fn new() -> Result<Self, macroquad::Error> {
+        Game::load_floor(START_FLOOR).await
+    }
+
+    // A floor as it starts, with Snake at the entrance
+    async fn load_floor(floor: &str) -> Result<Self, macroquad::Error> {
         let settings = Settings::new();
 
         let player = Player::new(settings).await;
-        let map = Map::new(settings, "assets/rooms/arrays/b1_f1.txt").await;
+        let map = Map::new(
+            settings,
+            &format!("assets/rooms/arrays/{}.txt", floor),
+            &format!("assets/rooms/spawns/{}.txt", floor),
+        )
+        .await;
 
         let rooms = &map.rooms;
         let found_room = rooms
@@ -43,49 +162,653 @@ impl Game {
         let current_room = found_room.unwrap().clone();
 
         let camera_position = Vec2 { x: 0.0, y: 0.0 };
+        let spawns = map.spawns.clone();
 
-        Ok(Self {
+        let mut game = Self {
             settings,
+            states: StateStack::new(State::Title),
+            running: true,
+            floor: floor.to_string(),
+            slots: Vec::new(),
+            typed: String::new(),
+            note: None,
+            checkpoint: None,
             player,
             map,
             current_room,
+            enemies: Vec::new(),
+            cameras: Vec::new(),
+            lasers: Vec::new(),
+            bosses: Vec::new(),
+            explosions: Vec::new(),
+            pickups: Vec::new(),
+            mines: Vec::new(),
+            prisoners: Vec::new(),
+            room_states: RoomStates::new(),
+            flags: HashSet::new(),
+            locked: HashSet::new(),
+            codec: Codec::new(settings, &format!("assets/codec/{}.txt", floor)).await,
+            scripts: Scripts::new(&format!("assets/scripts/{}.txt", floor)),
+            notification: Notification::new(),
+            hud: Hud::new(settings),
+            alert: Alert::new(settings).await,
+            audio: Audio::new(settings, &format!("assets/music/{}.txt", floor)).await,
+            noise_bus: NoiseBus::new(settings),
+            scent_trail: ScentTrail::new(settings),
             camera_position,
+            camera_focus: None,
             time_since_last_check: 0.0,
             check_interval: 1.0,
             delta_time: 0.0,
-        })
+            step_timer: 0.0,
+            touching_wall: false,
+            transmit_timer: 0.0,
+        };
+
+        for spawn in &spawns {
+            game.spawn(spawn).await;
+        }
+
+        Ok(game)
+    }
+
+    // The floor again, then everything the save says has changed on it
+    async fn from_save(save: &SaveData) -> Result<Self, macroquad::Error> {
+        let mut game = Game::load_floor(&save.floor).await?;
+        game.states = StateStack::new(State::Play);
+
+        let equip_menu = &mut game.player.equip_menu;
+        equip_menu.inventory =
+            Inventory::restore(save.rank, save.rescues, &save.weapons, &save.items);
+        equip_menu.equip_item(Item::Empty);
+        equip_menu.equip_item(save.left);
+        equip_menu.equip_weapon(save.right);
+
+        game.player.position = save.position;
+        if save.health > 0.0 {
+            game.player.health = save.health.min(game.player.max_health());
+        }
+        match game.map.find_room(&save.room) {
+            Some(room) => game.current_room = room.clone(),
+            None => eprintln!("Save in unknown room: {}", save.room),
+        }
+
+        game.flags = save.flags.iter().cloned().collect();
+        game.locked = save.locked.iter().cloned().collect();
+        game.room_states
+            .restore(&save.killed, &save.taken, save.clock, &save.left_at);
+        game.pickups
+            .retain(|pickup| !game.room_states.is_taken(&pickup.room, &pickup.id));
+        game.enemies
+            .retain(|enemy| !game.room_states.is_killed(&enemy.home_room, &enemy.id));
+
+        for prisoner in game.prisoners.iter_mut() {
+            if game.flags.contains(&prisoner.rescued_flag()) {
+                prisoner.rescue();
+            } else if game.flags.contains(&prisoner.killed_flag()) {
+                prisoner.kill();
+            }
+        }
+        for boss in game.bosses.iter_mut() {
+            if game.flags.contains(&boss.defeated_flag()) {
+                boss.defeat();
+            }
+        }
+
+        game.scripts.restore(&save.scripts, &game.flags);
+        game.codec.restore(&save.calls);
+        game.checkpoint();
+
+        Ok(game)
+    }
+
+    // The first floor with what the password remembers, everything owned half full
+    async fn from_password(password: Password) -> Result<Self, macroquad::Error> {
+        let mut game = Game::load_floor(START_FLOOR).await?;
+        game.states = StateStack::new(State::Play);
+
+        let mut inventory = Inventory::restore(password.rank, 0, &[], &[]);
+        for weapon in Weapon::ALL.into_iter().skip(1) {
+            if password.weapons & 1 << (weapon as u32 - 1) != 0 {
+                let ammo = inventory.weapon_capacity(weapon).unwrap_or(0) / 2;
+                inventory.add_weapon(weapon, ammo);
+            }
+        }
+        for item in Item::ALL.into_iter().skip(1) {
+            if password.items & 1 << (item as u32 - 1) != 0 {
+                let count = inventory.item_capacity(item).unwrap_or(0) / 2;
+                inventory.add_item(item, count);
+            }
+        }
+        game.player.equip_menu.inventory = inventory;
+        game.player.equip_menu.equip_item(Item::Empty);
+        game.player.health = game.player.max_health();
+
+        for (index, boss) in game.bosses.iter_mut().enumerate() {
+            if password.bosses & 1 << index != 0 {
+                boss.defeat();
+                game.flags.insert(boss.defeated_flag());
+            }
+        }
+        for (index, prisoner) in game.prisoners.iter_mut().enumerate() {
+            if password.prisoners & 1 << index != 0 {
+                prisoner.rescue();
+                game.flags.insert(prisoner.rescued_flag());
+            } else if password.killed & 1 << index != 0 {
+                prisoner.kill();
+                game.flags.insert(prisoner.killed_flag());
+            }
+        }
+        game.scripts.restore(&[], &game.flags);
+        game.checkpoint();
+
+        Ok(game)
+    }
+
+    // Where Continue picks up from, kept on disk for the title too
+    fn checkpoint(&mut self) {
+        let checkpoint = self.save_data();
+        if let Err(error) = checkpoint.write(&SaveData::checkpoint_path()) {
+            eprintln!("Couldn't write checkpoint: {}", error);
+        }
+        self.checkpoint = Some(checkpoint);
+    }
+
+    fn save_data(&self) -> SaveData {
+        let equip_menu = &self.player.equip_menu;
+        let inventory = &equip_menu.inventory;
+
+        SaveData {
+            floor: self.floor.clone(),
+            room: self.current_room.name.clone(),
+            position: self.player.position,
+            health: self.player.health,
+            rank: inventory.rank(),
+            rescues: inventory.rescues(),
+            weapons: inventory.weapons(),
+            items: inventory.items(),
+            left: Item::from_index(equip_menu.left_selected).unwrap_or(Item::Empty),
+            right: Weapon::from_index(equip_menu.right_selected).unwrap_or(Weapon::Empty),
+            flags: sorted(&self.flags),
+            killed: self.room_states.killed(),
+            taken: self.room_states.taken(),
+            clock: self.room_states.clock(),
+            left_at: self.room_states.left_at(),
+            locked: sorted(&self.locked),
+            scripts: self.scripts.fired(),
+            calls: self.codec.heard(),
+        }
+    }
+
+    // Owned kit by menu order, bosses and prisoners by spawn order
+    // None when the floor has more bosses or prisoners than a password holds
+    fn password(&self) -> Option<Password> {
+        if self.bosses.len() > save::MAX_BOSSES || self.prisoners.len() > save::MAX_PRISONERS {
+            return None;
+        }
+
+        let inventory = &self.player.equip_menu.inventory;
+        let mut password = Password {
+            rank: inventory.rank(),
+            weapons: 0,
+            items: 0,
+            bosses: 0,
+            prisoners: 0,
+            killed: 0,
+        };
+
+        for (weapon, _) in inventory.weapons() {
+            password.weapons |= 1 << (weapon as u32 - 1);
+        }
+        for (item, _) in inventory.items() {
+            password.items |= 1 << (item as u32 - 1);
+        }
+        for (index, boss) in self.bosses.iter().enumerate() {
+            if boss.is_defeated() {
+                password.bosses |= 1 << index;
+            }
+        }
+        for (index, prisoner) in self.prisoners.iter().enumerate() {
+            if self.flags.contains(&prisoner.rescued_flag()) {
+                password.prisoners |= 1 << index;
+            } else if self.flags.contains(&prisoner.killed_flag()) {
+                password.killed |= 1 << index;
+            }
+        }
+
+        Some(password)
     }
 
     pub async fn update(&mut self) {
         self.delta_time = Game::get_delta_time();
         self.settings.update();
-        self.player.update_equipment();
+        self.audio.update_volume();
+        self.states.update(self.delta_time);
 
-        if !self.player.equip_menu.pause {
+        match self.states.top() {
+            State::Title => self.title_update().await,
+            State::Play => self.play_update().await,
+            State::Pause => self.pause_update().await,
+            State::Codec => self.codec_update(),
+            State::Menu => self.menu_update(),
+            State::GameOver => self.game_over_update(),
+            State::Continue => self.continue_update().await,
+            State::Save => self.save_update(),
+            State::Load => self.load_update().await,
+            State::Password => self.password_update().await,
+        }
+    }
+
+    pub fn draw(&mut self) {
+        let state = self.states.top();
+        if self.states.bottom() != State::Title {
+            self.play_draw();
+        }
+
+        let ui = Ui::begin();
+        match state {
+            State::Title => {
+                self.states.draw_screen(
+                    &ui,
+                    TITLE,
+                    WHITE,
+                    &["Start", "Continue", "Load", "Password", "Quit"],
+                );
+                self.draw_note(&ui);
+            }
+            State::Pause => {
+                self.states.draw_screen(
+                    &ui,
+                    "PAUSE",
+                    WHITE,
+                    &["Resume", "Save", "Load", "Title", "Quit"],
+                );
+                self.draw_password(&ui);
+            }
+            State::GameOver => self.states.draw_screen(&ui, "GAME OVER", RED, &[]),
+            State::Continue => {
+                self.states
+                    .draw_screen(&ui, "CONTINUE?", WHITE, &["Yes", "No"]);
+                self.draw_password(&ui);
+            }
+            State::Save => self.draw_slots(&ui, "SAVE"),
+            State::Load => self.draw_slots(&ui, "LOAD"),
+            State::Password => {
+                let mut typed = format!("{:_<width$}", self.typed, width = save::PASSWORD_LENGTH);
+                typed.insert(save::PASSWORD_LENGTH / 2, '-');
+                self.states.draw_screen(&ui, "PASSWORD", WHITE, &[&typed]);
+                self.draw_note(&ui);
+            }
+            State::Play | State::Codec | State::Menu => {}
+        }
+
+        if self.settings.debug {
+            // Along the left edge, clear of the HUD corners and the notes
+            let text = format!("FPS: {:?}", get_fps());
+            ui.label(&text, Anchor::Left, vec2(24.0, 0.0), 32.0, WHITE);
+            // eprintln!("FPS: {:?}", get_fps());
+        }
+    }
+
+    // Main loop stops once this goes false
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    async fn title_update(&mut self) {
+        match self.states.choose(5) {
+            Some(0) => {
+                self.checkpoint();
+                self.states.replace(State::Play);
+            }
+            Some(1) => match SaveData::read(&SaveData::checkpoint_path()) {
+                Some(checkpoint) => {
+                    self.load(&checkpoint).await;
+                }
+                None => self.note = Some("No checkpoint yet".to_string()),
+            },
+            Some(2) => self.open_slots(State::Load),
+            Some(3) => self.open_password(),
+            Some(_) => self.running = false,
+            None => {}
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.running = false;
+        }
+    }
+
+    async fn play_update(&mut self) {
+        self.notification.update(self.delta_time);
+        self.hud.update();
+
+        if is_key_pressed(KeyCode::Escape) {
+            self.states.push(State::Pause);
+            return;
+        }
+
+        if self.codec.ring(&self.current_room.name, &self.flags) {
+            self.notification.push("Incoming call".to_string());
+        }
+        self.codec
+            .update(self.delta_time, &self.current_room.name, &self.flags);
+        if self.codec.is_open() {
+            self.states.push(State::Codec);
+            return;
+        }
+
+        self.player
+            .update_equipment(self.delta_time, self.current_room.gas);
+        if self.player.equip_menu.pause {
+            self.states.push(State::Menu);
+            return;
+        }
+
+        // A ration gets its chance first, from the equipment update
+        if self.player.health <= 0.0 {
+            self.states.push(State::GameOver);
+            return;
+        }
+
+        // Snake waits while a script has the camera
+        if self.camera_focus.is_none() {
             self.player.update(self.delta_time).await;
+        }
+
+        self.room_states.update(self.delta_time);
+        self.room_getter(get_frame_time()).await;
+        self.room_collision();
 
-            self.room_getter(get_frame_time());
-            self.room_collision();
+        self.scripts.update(
+            self.delta_time,
+            &self.current_room.name,
+            &mut self.flags,
+            self.codec.is_open(),
+        );
+        self.script_actions().await;
 
-            self.camera_update();
+        self.pickup_update();
+        self.prisoner_update();
+        self.enemies_update();
+        self.punch_collision();
+        self.footsteps();
+        self.noise_update();
+        self.alert_update();
+        self.boss_update().await;
+        self.bullet_collision();
+        self.projectile_update().await;
+        self.record_kills();
+        self.arena_lock();
+
+        self.audio.update(
+            self.delta_time,
+            &self.current_room.name,
+            self.alert.is_active(),
+        );
+        self.camera_update();
+
+        // A script may have put Snake on the line
+        if self.codec.is_open() {
+            self.states.push(State::Codec);
         }
     }
 
-    pub fn draw(&mut self) {
+    async fn pause_update(&mut self) {
+        if is_key_pressed(KeyCode::Escape) {
+            self.states.pop();
+            return;
+        }
+
+        match self.states.choose(5) {
+            Some(0) => self.states.pop(),
+            Some(1) => self.open_slots(State::Save),
+            Some(2) => self.open_slots(State::Load),
+            Some(3) => self.restart().await,
+            Some(_) => self.running = false,
+            None => {}
+        }
+    }
+
+    // Reads what's in each slot before listing them
+    fn open_slots(&mut self, state: State) {
+        self.slots = (0..save::SLOTS)
+            .map(|slot| SaveData::read(&SaveData::slot_path(slot)))
+            .collect();
+        self.note = None;
+        self.states.push(state);
+    }
+
+    fn open_password(&mut self) {
+        self.typed.clear();
+        self.note = None;
+        self.states.push(State::Password);
+    }
+
+    // The slots, then Back
+    fn save_update(&mut self) {
+        if is_key_pressed(KeyCode::Escape) {
+            self.states.pop();
+            return;
+        }
+
+        match self.states.choose(save::SLOTS + 1) {
+            Some(slot) if slot < save::SLOTS => {
+                let save = self.save_data();
+                match save.write(&SaveData::slot_path(slot)) {
+                    Ok(()) => {
+                        self.note = Some(format!("Saved to slot {}", slot + 1));
+                        self.slots[slot] = Some(save);
+                    }
+                    Err(error) => {
+                        eprintln!("Couldn't save slot {}: {}", slot + 1, error);
+                        self.note = Some("Couldn't save".to_string());
+                    }
+                }
+            }
+            Some(_) => self.states.pop(),
+            None => {}
+        }
+    }
+
+    async fn load_update(&mut self) {
+        if is_key_pressed(KeyCode::Escape) {
+            self.states.pop();
+            return;
+        }
+
+        match self.states.choose(save::SLOTS + 1) {
+            Some(slot) if slot < save::SLOTS => match self.slots[slot].clone() {
+                Some(save) => {
+                    self.load(&save).await;
+                }
+                None => self.note = Some(format!("Slot {} is empty", slot + 1)),
+            },
+            Some(_) => self.states.pop(),
+            None => {}
+        }
+    }
+
+    async fn password_update(&mut self) {
+        if is_key_pressed(KeyCode::Escape) {
+            self.states.pop();
+            return;
+        }
+
+        while let Some(character) = get_char_pressed() {
+            if character.is_ascii_alphanumeric() && self.typed.len() < save::PASSWORD_LENGTH {
+                self.typed.push(character.to_ascii_uppercase());
+                self.note = None;
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.typed.pop();
+        }
+        if !is_key_pressed(KeyCode::Enter) {
+            return;
+        }
+
+        match Password::decode(&self.typed) {
+            Some(password) => match Game::from_password(password).await {
+                Ok(game) => *self = game,
+                Err(error) => eprintln!("Couldn't start from password: {:?}", error),
+            },
+            None => self.note = Some("Wrong password".to_string()),
+        }
+    }
+
+    fn codec_update(&mut self) {
+        self.codec
+            .update(self.delta_time, &self.current_room.name, &self.flags);
+        if !self.codec.is_open() {
+            self.states.pop();
+        }
+    }
+
+    // The game holds still, statuses included, only the menu moves
+    fn menu_update(&mut self) {
+        self.player.equip_menu.update();
+        if !self.player.equip_menu.pause {
+            self.states.pop();
+        }
+    }
+
+    // Holds on the fallen Snake a moment before asking
+    fn game_over_update(&mut self) {
+        if self.states.timer() >= GAME_OVER_TIME || is_key_pressed(KeyCode::Enter) {
+            self.states.replace(State::Continue);
+        }
+    }
+
+    async fn continue_update(&mut self) {
+        match self.states.choose(2) {
+            Some(0) => self.resume().await,
+            Some(_) => self.restart().await,
+            None => {}
+        }
+    }
+
+    // Back at the last checkpoint, the room as it was when he walked in
+    async fn resume(&mut self) {
+        let checkpoint = match self.checkpoint.clone() {
+            Some(checkpoint) => checkpoint,
+            None => return self.restart().await,
+        };
+
+        if self.load(&checkpoint).await {
+            let least = self.player.max_health() * CONTINUE_HEALTH;
+            self.player.health = self.player.health.max(least);
+        }
+    }
+
+    // True once the save has taken over
+    async fn load(&mut self, save: &SaveData) -> bool {
+        match Game::from_save(save).await {
+            Ok(game) => {
+                *self = game;
+                true
+            }
+            Err(error) => {
+                eprintln!("Couldn't load: {:?}", error);
+                self.note = Some("Couldn't load".to_string());
+                false
+            }
+        }
+    }
+
+    // A fresh game, back at the title
+    async fn restart(&mut self) {
+        match Game::new().await {
+            Ok(game) => *self = game,
+            Err(error) => eprintln!("Couldn't restart: {:?}", error),
+        }
+    }
+
+    fn draw_slots(&self, ui: &Ui, heading: &str) {
+        let mut options: Vec<String> = self
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(slot, save)| match save {
+                Some(save) => format!("{}: {}", slot + 1, save.describe()),
+                None => format!("{}: Empty", slot + 1),
+            })
+            .collect();
+        options.push("Back".to_string());
+
+        let options: Vec<&str> = options.iter().map(String::as_str).collect();
+        self.states.draw_screen(ui, heading, WHITE, &options);
+        self.draw_note(ui);
+    }
+
+    fn draw_note(&self, ui: &Ui) {
+        if let Some(note) = &self.note {
+            self.states.draw_note(ui, note, YELLOW);
+        }
+    }
+
+    // Written down to pick up from later, without a save file
+    fn draw_password(&self, ui: &Ui) {
+        let text = match self.password() {
+            Some(password) => format!("PASSWORD: {}", password.encode()),
+            None => "NO PASSWORD ON THIS FLOOR".to_string(),
+        };
+        self.states.draw_note(ui, &text, GRAY);
+    }
+
+    fn play_draw(&mut self) {
+        // World first, following Snake
+        set_camera(&self.world_camera());
+
         self.map.draw();
+        self.scent_trail.draw();
+        for pickup in &self.pickups {
+            pickup.draw();
+        }
+        for prisoner in &self.prisoners {
+            prisoner.draw();
+        }
+        if self.player.item_effect().reveals_mines() || self.settings.debug {
+            for mine in &self.mines {
+                mine.draw();
+            }
+        }
+        for laser in &self.lasers {
+            laser.draw(self.player.wears_goggles());
+        }
+        for camera in &self.cameras {
+            camera.draw();
+        }
+        for enemy in &self.enemies {
+            enemy.draw();
+        }
+        for boss in &self.bosses {
+            boss.draw();
+        }
+        for explosion in &self.explosions {
+            explosion.draw();
+        }
+        self.noise_bus.draw();
         self.player.draw();
-        self.player.equip_menu.draw(self.camera_position);
 
-        if self.settings.debug {
-            draw_text(
-                &format!("FPS: {:?}", get_fps()),
-                self.player.position.x,
-                self.player.position.y,
-                64.0,
-                WHITE,
-            );
-            // eprintln!("FPS: {:?}", get_fps());
+        let light = self.player.item_effect().lights_dark();
+        self.current_room
+            .draw_darkness(light.then(|| self.player.collider.center()));
+
+        self.alert.draw();
+        if self.player.item_effect().points_to_objective() {
+            self.draw_compass();
         }
+
+        // Then the UI, in screen space
+        let ui = Ui::begin();
+        self.hud.draw(&ui, &self.player, &self.alert);
+        for boss in &self.bosses {
+            if boss.room == self.current_room.name {
+                boss.draw_health_bar(&ui);
+            }
+        }
+        self.notification.draw(&ui);
+        self.player.equip_menu.draw(&ui);
+        self.codec.draw(&ui);
     }
 
     fn get_delta_time() -> f32 {
@@ -105,45 +828,63 @@ impl Game {
     }
 
     fn camera_update(&mut self) {
+        if let Some((target, time)) = self.camera_focus {
+            self.camera_position = target;
+            self.camera_focus =
+                (time > self.delta_time).then_some((target, time - self.delta_time));
+            return;
+        }
+
         self.camera_position = self.player.position;
 
+        // Standing still with binoculars looks a room ahead
+        if self.player.item_effect().peeks() && !self.player.is_walking() {
+            let room_size = self.current_room.bounds.size();
+            self.camera_position += self.player.direction.vector() * room_size;
+        }
+    }
+
+    fn world_camera(&self) -> Camera2D {
         if self.settings.zoom {
-            set_camera(
-                &(Camera2D {
-                    zoom: vec2(1.0 / screen_width() / 2.0, 1.0 / screen_height() / 2.0), // half zoom
-                    target: self.camera_position,
-                    ..Default::default()
-                }),
-            );
+            Camera2D {
+                zoom: vec2(1.0 / screen_width() / 2.0, 1.0 / screen_height() / 2.0), // half zoom
+                target: self.camera_position,
+                ..Default::default()
+            }
         } else {
-            set_camera(
-                &(Camera2D {
-                    zoom: vec2((1.0 / screen_width()) * 2.0, (1.0 / screen_height()) * 2.0), // full view
-                    target: self.camera_position,
-                    ..Default::default()
-                }),
-            );
+            Camera2D {
+                zoom: vec2((1.0 / screen_width()) * 2.0, (1.0 / screen_height()) * 2.0), // full view
+                target: self.camera_position,
+                ..Default::default()
+            }
         }
     }
 
-    fn room_getter(&mut self, delta_time: f32) {
+    async fn room_getter(&mut self, delta_time: f32) {
         self.time_since_last_check += delta_time;
 
-        if self.time_since_last_check >= self.check_interval {
-            if !self
+        if self.time_since_last_check >= self.check_interval
+            && !self
                 .current_room
                 .bounds
                 .contains(self.player.collider.center())
-            {
-                let rooms = &self.map.rooms;
-                let found_room = rooms
-                    .iter()
-                    .find(|room| room.bounds.contains(self.player.collider.center()));
-                if found_room.is_some() {
-                    self.current_room = found_room.unwrap().clone();
-                } else {
-                    eprintln!("OOB: {:?}", self.player.position);
+        {
+            let rooms = &self.map.rooms;
+            let found_room = rooms
+                .iter()
+                .find(|room| room.bounds.contains(self.player.collider.center()));
+            if let Some(room) = found_room.cloned() {
+                let left = std::mem::replace(&mut self.current_room, room);
+                let entered = self.current_room.name.clone();
+                if self
+                    .room_states
+                    .enter(&left.name, &entered, self.current_room.respawn)
+                {
+                    self.respawn_room(&entered).await;
                 }
+                self.checkpoint();
+            } else {
+                eprintln!("OOB: {:?}", self.player.position);
             }
         }
     }
@@ -151,6 +892,7 @@ impl Game {
     fn room_collision(&mut self) {
         let colliders = &self.current_room.collider_map.colliders;
 
+        let mut hit = false;
         for collider in colliders {
             if collider.overlaps(&self.player.collider) {
                 // Easy maths
@@ -194,8 +936,714 @@ impl Game {
                     self.player.position.x = collider.x - self.player.bounds.w - buffer;
                 }
                 self.player.col_arr[closest_index] = true;
+                hit = true;
                 break;
             }
         }
+
+        if hit && !self.touching_wall {
+            self.audio.play("bump");
+        }
+        self.touching_wall = hit;
+    }
+
+    fn pickup_update(&mut self) {
+        let mut found = Vec::new();
+        for pickup in self.pickups.iter_mut() {
+            pickup.update();
+            if pickup.room != self.current_room.name
+                || !pickup.bounds.overlaps(&self.player.collider)
+            {
+                continue;
+            }
+
+            found.push(pickup.kind);
+            self.room_states.take(&pickup.room, &pickup.id);
+        }
+        for kind in found {
+            self.give(kind);
+        }
+
+        // Gone until the room says otherwise, the ids are what saves remember
+        self.pickups
+            .retain(|pickup| !self.room_states.is_taken(&pickup.room, &pickup.id));
+    }
+
+    // The dead are cleared away, their room remembers them
+    fn record_kills(&mut self) {
+        for enemy in self.enemies.iter().filter(|enemy| !enemy.is_alive()) {
+            self.room_states.kill(&enemy.home_room, &enemy.id);
+        }
+        self.enemies.retain(|enemy| enemy.is_alive());
+    }
+
+    // Guards and ammo back where the map put them, anyone still about stays put
+    async fn respawn_room(&mut self, name: &str) {
+        let bounds = match self.map.find_room(name) {
+            Some(room) => room.bounds,
+            None => return,
+        };
+        let spawns: Vec<Spawn> = self
+            .map
+            .spawns
+            .iter()
+            .filter(|spawn| spawn.room == name)
+            .cloned()
+            .collect();
+
+        for spawn in spawns {
+            let id = spawn::id(name, spawn.world_position(bounds));
+            let back = match spawn.kind {
+                SpawnKind::Guard | SpawnKind::Dog => {
+                    !self.enemies.iter().any(|enemy| enemy.id == id)
+                }
+                SpawnKind::Pickup => {
+                    matches!(
+                        PickupKind::from_args(&spawn.args),
+                        Some(PickupKind::Ammo(..))
+                    ) && self.room_states.give_back(name, &id)
+                }
+                _ => false,
+            };
+            if back {
+                self.spawn(&spawn).await;
+            }
+        }
+    }
+
+    // Empty hands take up whatever was just found
+    fn give(&mut self, kind: PickupKind) {
+        let equip_menu = &mut self.player.equip_menu;
+        match kind {
+            PickupKind::Item(item, count) => {
+                equip_menu.inventory.add_item(item, count);
+                if equip_menu.left_selected == Item::Empty as usize {
+                    equip_menu.equip_item(item);
+                }
+            }
+            PickupKind::Weapon(weapon, ammo) => {
+                equip_menu.inventory.add_weapon(weapon, ammo);
+                if equip_menu.right_selected == Weapon::Empty as usize {
+                    equip_menu.equip_weapon(weapon);
+                }
+            }
+            PickupKind::Ammo(weapon, rounds) => equip_menu.inventory.add_ammo(weapon, rounds),
+        }
+
+        self.notification.push(kind.describe());
+        self.audio.play("pickup");
+    }
+
+    // Map spawns at load, script spawns whenever they come up
+    async fn spawn(&mut self, spawn: &Spawn) {
+        let settings = self.settings;
+        let room = match self.map.find_room(&spawn.room) {
+            Some(room) => room,
+            None => {
+                eprintln!("Spawn in unknown room: {}", spawn.room);
+                return;
+            }
+        };
+
+        let position = spawn.world_position(room.bounds);
+        match spawn.kind {
+            SpawnKind::Guard => {
+                let kind = EnemyKind::Guard;
+                self.enemies
+                    .push(Enemy::new(settings, kind, position, &room.name).await);
+            }
+            SpawnKind::Dog => {
+                let kind = EnemyKind::Dog;
+                self.enemies
+                    .push(Enemy::new(settings, kind, position, &room.name).await);
+            }
+            SpawnKind::Camera => {
+                let facing = spawn
+                    .args
+                    .first()
+                    .and_then(|arg| Direction::from_name(arg))
+                    .unwrap_or(Direction::Down);
+                self.cameras
+                    .push(SecurityCamera::new(settings, position, facing, &room.name).await);
+            }
+            SpawnKind::Laser => {
+                self.lasers
+                    .push(Laser::new(settings, spawn.area(room.bounds), &room.name));
+            }
+            SpawnKind::Boss => match spawn.args.first() {
+                Some(name) => {
+                    if let Some(boss) = Boss::new(settings, name, position, &room.name).await {
+                        self.bosses.push(boss);
+                    }
+                }
+                None => eprintln!("Boss spawn without a name in room: {}", room.name),
+            },
+            SpawnKind::Pickup => match PickupKind::from_args(&spawn.args) {
+                Some(kind) => {
+                    let equip_menu = &self.player.equip_menu;
+                    let icon = match kind {
+                        PickupKind::Item(item, _) => equip_menu.item_icon(item),
+                        PickupKind::Weapon(weapon, _) | PickupKind::Ammo(weapon, _) => {
+                            equip_menu.weapon_icon(weapon)
+                        }
+                    };
+                    self.pickups
+                        .push(Pickup::new(settings, kind, position, &room.name, icon));
+                }
+                None => eprintln!("Bad pickup: {:?}", spawn.args),
+            },
+            SpawnKind::Mine => {
+                let center = position + Vec2::splat(spawn::CELL_SIZE / 2.0);
+                let mut mine = Bullet::new(Projectile::Mine, center, Vec2::ZERO, center).await;
+                mine.arm();
+                self.mines.push(mine);
+            }
+            // Look on the sheet, then the name their flag goes by
+            SpawnKind::Prisoner => {
+                let look = spawn
+                    .args
+                    .first()
+                    .and_then(|arg| arg.parse::<u32>().ok())
+                    .unwrap_or(0);
+                let name =
+                    spawn.args.get(1).cloned().unwrap_or_else(|| {
+                        format!("{}:{}:{}", room.name, spawn.cell.x, spawn.cell.y)
+                    });
+                self.prisoners
+                    .push(Prisoner::new(settings, &name, look, position, &room.name).await);
+            }
+            // Room flags, already set on the rooms by the map
+            SpawnKind::Floor | SpawnKind::Gas | SpawnKind::Dark | SpawnKind::Respawn => {}
+        }
+    }
+
+    // Whatever the scripts asked for this frame
+    async fn script_actions(&mut self) {
+        let actions: Vec<script::Action> = self.scripts.actions.drain(..).collect();
+        for action in actions {
+            match action {
+                script::Action::Say(says) => self.codec.play(&says),
+                script::Action::Notify(text) => self.notification.push(text),
+                script::Action::Give(kind) => self.give(kind),
+                script::Action::Spawn(spawn) => self.spawn(&spawn).await,
+                script::Action::Lock(room) => {
+                    self.locked.insert(room);
+                }
+                script::Action::Unlock(room) => {
+                    self.locked.remove(&room);
+                }
+                script::Action::Camera { room, cell, time } => match self.map.find_room(&room) {
+                    Some(room) => {
+                        let target = room.bounds.point() + cell * spawn::CELL_SIZE;
+                        self.camera_focus = Some((target, time));
+                    }
+                    None => eprintln!("Camera on unknown room: {}", room),
+                },
+                script::Action::Sound(name) => self.audio.play(&name),
+            }
+        }
+    }
+
+    fn prisoner_update(&mut self) {
+        let mut rescued = Vec::new();
+        for prisoner in self.prisoners.iter_mut() {
+            prisoner.update();
+            if prisoner.is_captive()
+                && prisoner.room == self.current_room.name
+                && prisoner.collider.overlaps(&self.player.collider)
+            {
+                prisoner.rescue();
+                rescued.push(prisoner.rescued_flag());
+            }
+        }
+
+        for flag in rescued {
+            self.flags.insert(flag);
+            self.notification.push("Prisoner rescued".to_string());
+            if self.player.equip_menu.inventory.rescue() {
+                self.rank_up();
+            }
+        }
+    }
+
+    // More life and room for ammo, topped up on the way
+    fn rank_up(&mut self) {
+        self.player.health = self.player.max_health();
+        self.notification.push("Rank up".to_string());
+    }
+
+    fn prisoners_killed(&mut self, killed: u32) {
+        for _ in 0..killed {
+            self.player.equip_menu.inventory.demote();
+            self.notification
+                .push("Prisoner killed, rank down".to_string());
+        }
+        self.player.health = self.player.health.min(self.player.max_health());
+    }
+
+    fn enemies_update(&mut self) {
+        let masked = self.player.masks_scent();
+        self.scent_trail
+            .update(self.delta_time, self.player.collider.center(), masked);
+
+        for enemy in self.enemies.iter_mut() {
+            // Dogs on the trail and guards on a route follow Snake out of the room
+            if enemy.room != self.current_room.name && !enemy.is_tracking() && !enemy.has_route() {
+                continue;
+            }
+
+            let room = match self.map.find_room(&enemy.room) {
+                Some(room) => room,
+                None => continue,
+            };
+
+            enemy.sniff(&self.scent_trail);
+            enemy.update(self.delta_time, &self.map);
+
+            if !room.bounds.contains(enemy.collider.center()) {
+                if let Some(next_room) = self.map.room_at(enemy.collider.center()) {
+                    enemy.room = next_room.name.clone();
+                }
+            }
+
+            if enemy.collider.overlaps(&self.player.collider) && enemy.try_bite() {
+                self.player.take_damage(BITE_DAMAGE);
+                if enemy.kind == EnemyKind::Dog {
+                    self.player
+                        .statuses
+                        .apply(StatusKind::Poisoned, BITE_POISON);
+                }
+            }
+        }
+    }
+
+    fn punch_collision(&mut self) {
+        let hitbox = match self.player.punch_hitbox() {
+            Some(hitbox) => hitbox,
+            None => return,
+        };
+        self.player.spend_punch();
+        self.audio.play("punch");
+
+        let push = self.player.direction.vector() * PUNCH_KNOCKBACK;
+        let mut landed = false;
+        for enemy in self.enemies.iter_mut() {
+            if enemy.is_alive() && enemy.collider.overlaps(&hitbox) {
+                enemy.hit(PUNCH_DAMAGE, push);
+                landed = true;
+            }
+        }
+        for boss in self.bosses.iter_mut() {
+            if !boss.is_defeated() && boss.collider.overlaps(&hitbox) {
+                boss.hit(PUNCH_DAMAGE, Weapon::Empty);
+                landed = true;
+            }
+        }
+
+        // Knocking on a wall draws guards over
+        if !landed {
+            let colliders = &self.current_room.collider_map.colliders;
+            if colliders.iter().any(|collider| collider.overlaps(&hitbox)) {
+                let origin = self.player.collider.center();
+                self.noise_bus.emit(Noise::new(NoiseKind::Punch, origin));
+            }
+        }
+    }
+
+    fn footsteps(&mut self) {
+        if !self.player.is_walking() {
+            self.step_timer = 0.0;
+            return;
+        }
+
+        self.step_timer += self.delta_time;
+        if self.step_timer < STEP_INTERVAL {
+            return;
+        }
+        self.step_timer = 0.0;
+        self.audio.play("footstep");
+
+        // Only noisy floors carry to the guards
+        let feet = self.player.collider.center();
+        let noisy = self
+            .current_room
+            .noisy_floors
+            .iter()
+            .any(|floor| floor.contains(feet));
+        if noisy {
+            self.noise_bus.emit(Noise::new(NoiseKind::Footstep, feet));
+        }
+    }
+
+    fn noise_update(&mut self) {
+        for sound in self.player.sounds.drain(..) {
+            self.audio.play(sound);
+        }
+        for noise in self.player.noises.drain(..) {
+            self.noise_bus.emit(noise);
+        }
+        self.noise_bus.update(self.delta_time);
+
+        // Walls between the noise and a guard muffle it
+        let collider_map = &self.current_room.collider_map;
+        for noise in self.noise_bus.drain() {
+            for enemy in self.enemies.iter_mut() {
+                if enemy.room != self.current_room.name {
+                    continue;
+                }
+
+                let ear = enemy.collider.center();
+                let walls = collider_map.walls_between(noise.origin, ear);
+                if noise.heard_at(ear, walls) {
+                    enemy.investigate(noise.origin, &self.map);
+                }
+            }
+        }
+    }
+
+    fn alert_update(&mut self) {
+        let was_active = self.alert.is_active();
+        let target = self.player.collider.center();
+        let collider_map = &self.current_room.collider_map;
+        let visibility = self.player.visibility();
+        let disguised = self.player.statuses.has(StatusKind::Disguised);
+
+        for enemy in self.enemies.iter() {
+            if enemy.room != self.current_room.name
+                || enemy.kind != EnemyKind::Guard
+                || !enemy.is_awake()
+            {
+                continue;
+            }
+
+            // A box only gives itself away up close, a uniform closer still
+            let eye = enemy.collider.center();
+            let distance = eye.distance(target);
+            if distance > GUARD_VISION.range * visibility
+                || (disguised && distance > DISGUISE_RANGE)
+            {
+                continue;
+            }
+            if GUARD_VISION.sees(eye, enemy.direction.vector(), target, collider_map) {
+                self.alert
+                    .raise(target, vec2(enemy.bounds.center().x, enemy.bounds.y));
+            }
+        }
+
+        for camera in self.cameras.iter_mut() {
+            if camera.room != self.current_room.name {
+                continue;
+            }
+
+            camera.update(self.delta_time);
+            let eye = camera.bounds.center();
+            if disguised && eye.distance(target) > DISGUISE_RANGE {
+                continue;
+            }
+            if camera.sees(target, visibility, collider_map) {
+                self.alert
+                    .raise(target, vec2(camera.bounds.center().x, camera.bounds.y));
+            }
+        }
+
+        for laser in self.lasers.iter_mut() {
+            laser.update();
+            if laser.room == self.current_room.name && laser.crossed_by(self.player.collider) {
+                self.alert.raise(
+                    target,
+                    vec2(self.player.bounds.center().x, self.player.bounds.y),
+                );
+            }
+        }
+
+        self.alert.update(self.delta_time);
+        if self.alert.is_active() && !was_active {
+            self.audio.play("alert");
+        }
+
+        // A transmitter in the bag keeps telling the room where Snake is
+        if self.player.is_transmitting() {
+            self.transmit_timer += self.delta_time;
+            if self.transmit_timer >= TRANSMIT_INTERVAL {
+                self.transmit_timer = 0.0;
+                for enemy in self.enemies.iter_mut() {
+                    if enemy.room == self.current_room.name
+                        && enemy.is_awake()
+                        && !enemy.is_tracking()
+                    {
+                        enemy.investigate(target, &self.map);
+                    }
+                }
+            }
+        } else {
+            self.transmit_timer = 0.0;
+        }
+
+        // Everyone in the room heads for where Snake was last seen
+        if self.alert.is_active() {
+            for enemy in self.enemies.iter_mut() {
+                if enemy.room == self.current_room.name && enemy.is_awake() && !enemy.is_tracking()
+                {
+                    enemy.investigate(self.alert.last_known, &self.map);
+                }
+            }
+        }
+    }
+
+    async fn boss_update(&mut self) {
+        let target = self.player.collider.center();
+        let arena = self.current_room.bounds;
+        let colliders = &self.current_room.collider_map.colliders;
+
+        let mut promoted = false;
+        for boss in self.bosses.iter_mut() {
+            if boss.room != self.current_room.name {
+                continue;
+            }
+
+            // The fight starts once Snake is all the way in
+            let collider = self.player.collider;
+            if arena.contains(collider.point())
+                && arena.contains(collider.point() + collider.size())
+            {
+                boss.engage();
+            }
+
+            boss.update(self.delta_time, target, colliders).await;
+
+            for bullet in boss.bullets.iter_mut() {
+                if bullet.alive
+                    && !bullet.is_airborne()
+                    && bullet.bounds().overlaps(&self.player.collider)
+                {
+                    bullet.expire();
+                    self.player.take_damage(BOSS_SHOT_DAMAGE);
+                }
+            }
+
+            if boss.is_defeated() && !self.flags.contains(&boss.defeated_flag()) {
+                self.flags.insert(boss.defeated_flag());
+            }
+
+            // Beating a boss is worth a rank on top of the reward
+            if let Some(item) = boss.take_reward() {
+                self.player.equip_menu.inventory.add_item(item, 1);
+                self.player.equip_menu.inventory.promote();
+                self.player.equip_menu.equip_item(item);
+                promoted = true;
+            }
+        }
+
+        if promoted {
+            self.rank_up();
+        }
+    }
+
+    fn bullet_collision(&mut self) {
+        let colliders = &self.current_room.collider_map.colliders;
+
+        // Explosives leave the damage to their blast
+        let mut killed = 0;
+        for bullet in self.player.bullets.iter_mut() {
+            if !bullet.alive || bullet.is_airborne() {
+                continue;
+            }
+            let bounds = bullet.bounds();
+            let damage = if bullet.explodes() {
+                0.0
+            } else {
+                BULLET_DAMAGE
+            };
+
+            for enemy in self.enemies.iter_mut() {
+                if bullet.alive && enemy.is_alive() && enemy.collider.overlaps(&bounds) {
+                    enemy.hit(damage, Vec2::ZERO);
+                    bullet.expire();
+                }
+            }
+            for boss in self.bosses.iter_mut() {
+                if bullet.alive && !boss.is_defeated() && boss.bounds.overlaps(&bounds) {
+                    boss.hit(damage, bullet.weapon);
+                    bullet.expire();
+                }
+            }
+            for prisoner in self.prisoners.iter_mut() {
+                if bullet.alive && prisoner.is_captive() && prisoner.bounds.overlaps(&bounds) {
+                    if !bullet.explodes() {
+                        prisoner.kill();
+                        self.flags.insert(prisoner.killed_flag());
+                        killed += 1;
+                    }
+                    bullet.expire();
+                }
+            }
+        }
+
+        // Mines go off under Snake
+        for mine in self.mines.iter_mut() {
+            if mine.alive && mine.bounds().overlaps(&self.player.collider) {
+                mine.expire();
+            }
+        }
+
+        // Walls stop everyone's bullets
+        let bullets = self.player.bullets.iter_mut().chain(
+            self.bosses
+                .iter_mut()
+                .flat_map(|boss| boss.bullets.iter_mut()),
+        );
+        for bullet in bullets {
+            if bullet.is_airborne() || bullet.is_placed() {
+                continue;
+            }
+            if colliders
+                .iter()
+                .any(|collider| collider.overlaps(&bullet.bounds()))
+            {
+                bullet.expire();
+            }
+        }
+
+        self.prisoners_killed(killed);
+    }
+
+    async fn projectile_update(&mut self) {
+        // Snake's homing missiles go for whoever is nearest in the room
+        let mut targets: Vec<Vec2> = self
+            .enemies
+            .iter()
+            .filter(|enemy| enemy.room == self.current_room.name && enemy.is_alive())
+            .map(|enemy| enemy.collider.center())
+            .collect();
+        targets.extend(
+            self.bosses
+                .iter()
+                .filter(|boss| boss.room == self.current_room.name && !boss.is_defeated())
+                .map(|boss| boss.collider.center()),
+        );
+        for bullet in self.player.bullets.iter_mut() {
+            let position = bullet.position();
+            let nearest = targets.iter().min_by(|a, b| {
+                a.distance(position)
+                    .partial_cmp(&b.distance(position))
+                    .unwrap()
+            });
+            if let Some(target) = nearest {
+                bullet.steer(*target, self.delta_time);
+            }
+        }
+
+        // Snake's blasts hurt everyone, the bosses' only hurt Snake
+        let mut blasts: Vec<(Blast, bool)> = self
+            .player
+            .blasts
+            .drain(..)
+            .map(|blast| (blast, true))
+            .collect();
+        for boss in self.bosses.iter_mut() {
+            blasts.extend(boss.blasts.drain(..).map(|blast| (blast, false)));
+        }
+        blasts.extend(
+            self.mines
+                .iter_mut()
+                .filter_map(|mine| mine.blast.take())
+                .map(|blast| (blast, false)),
+        );
+        self.mines.retain(|mine| mine.alive);
+
+        let mut killed = 0;
+        for (blast, from_player) in blasts {
+            self.noise_bus
+                .emit(Noise::new(NoiseKind::Explosion, blast.origin));
+            self.audio.play("explosion");
+
+            let in_reach = |rect: Rect| rect.center().distance(blast.origin) <= blast.radius;
+            if in_reach(self.player.collider) {
+                self.player.take_damage(blast.player_damage);
+                self.player.statuses.apply(StatusKind::Stunned, BLAST_STUN);
+            }
+
+            if from_player {
+                for enemy in self.enemies.iter_mut() {
+                    if enemy.is_alive() && in_reach(enemy.collider) {
+                        let push = (enemy.collider.center() - blast.origin).normalize_or_zero();
+                        enemy.hit(blast.damage, push * PUNCH_KNOCKBACK);
+                    }
+                }
+                for boss in self.bosses.iter_mut() {
+                    if !boss.is_defeated() && in_reach(boss.collider) {
+                        boss.hit(blast.damage, blast.weapon);
+                    }
+                }
+                for prisoner in self.prisoners.iter_mut() {
+                    if prisoner.is_captive() && in_reach(prisoner.collider) {
+                        prisoner.kill();
+                        self.flags.insert(prisoner.killed_flag());
+                        killed += 1;
+                    }
+                }
+            }
+
+            self.explosions
+                .push(Explosion::new(self.settings, blast).await);
+        }
+
+        for explosion in self.explosions.iter_mut() {
+            explosion.update(self.delta_time);
+        }
+        self.explosions.retain(|explosion| !explosion.done);
+
+        self.prisoners_killed(killed);
     }
+
+    // Arrow over Snake's head towards the nearest boss still standing
+    fn draw_compass(&self) {
+        let origin = self.player.collider.center();
+        let objective = self
+            .bosses
+            .iter()
+            .filter(|boss| !boss.is_defeated())
+            .map(|boss| boss.collider.center())
+            .min_by(|a, b| a.distance(origin).partial_cmp(&b.distance(origin)).unwrap());
+
+        let heading = match objective {
+            Some(objective) => (objective - origin).normalize_or_zero(),
+            None => return,
+        };
+        if heading == Vec2::ZERO {
+            return;
+        }
+
+        let tip = origin + heading * (COMPASS_DISTANCE + COMPASS_SIZE);
+        let base = origin + heading * COMPASS_DISTANCE;
+        let side = heading.perp() * COMPASS_SIZE / 2.0;
+        draw_triangle(tip, base + side, base - side, YELLOW);
+    }
+
+    // Arena doors stay shut until the boss goes down, scripts can shut any room
+    fn arena_lock(&mut self) {
+        let locked = self.locked.contains(&self.current_room.name)
+            || self
+                .bosses
+                .iter()
+                .any(|boss| boss.room == self.current_room.name && boss.is_fighting());
+        if !locked {
+            return;
+        }
+
+        let arena = self.current_room.bounds;
+        let collider = self.player.collider;
+        let push = Vec2::new(
+            (arena.x - collider.x).max(0.0) + (arena.right() - collider.right()).min(0.0),
+            (arena.y - collider.y).max(0.0) + (arena.bottom() - collider.bottom()).min(0.0),
+        );
+        self.player.position += push;
+    }
+}
+
+// Sets saved in order, so the same game saves the same file
+fn sorted(set: &HashSet<String>) -> Vec<String> {
+    let mut sorted: Vec<String> = set.iter().cloned().collect();
+    sorted.sort();
+    sorted
 }
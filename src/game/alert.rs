@@ -0,0 +1,97 @@
+use macroquad::prelude::*;
+
+use crate::game::Settings;
+
+#[derive(Debug, Clone)]
+pub struct Alert {
+    settings: Settings,
+    texture: Texture2D,
+    phase: Phase,
+    timer: f32,
+    exclaim: f32,
+    spotter: Vec2,
+    pub last_known: Vec2,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Phase {
+    Clear,
+    Alert,
+}
+
+const SCALE: f32 = 2.0;
+const ALERT_TIME: f32 = 10.0;
+const EXCLAIM_TIME: f32 = 1.0;
+
+impl Alert {
+    pub async fn new(settings: Settings) -> Self {
+        // Load Textures
+        let texture = load_texture("assets/effects/!.png").await.unwrap();
+        texture.set_filter(FilterMode::Nearest);
+
+        Self {
+            settings,
+            texture,
+            phase: Phase::Clear,
+            timer: 0.0,
+            exclaim: 0.0,
+            spotter: Vec2::ZERO,
+            last_known: Vec2::ZERO,
+        }
+    }
+
+    // Spotted at position by whatever sits at spotter
+    pub fn raise(&mut self, position: Vec2, spotter: Vec2) {
+        if self.phase == Phase::Clear {
+            self.exclaim = EXCLAIM_TIME;
+            self.spotter = spotter;
+        }
+
+        self.phase = Phase::Alert;
+        self.timer = ALERT_TIME;
+        self.last_known = position;
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.settings.update();
+
+        self.exclaim = (self.exclaim - delta_time).max(0.0);
+
+        if self.phase == Phase::Alert {
+            self.timer -= delta_time;
+            if self.timer <= 0.0 {
+                self.phase = Phase::Clear;
+                self.timer = 0.0;
+            }
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.phase == Phase::Alert
+    }
+
+    // Share of the countdown still to run
+    pub fn time_left(&self) -> f32 {
+        self.timer / ALERT_TIME
+    }
+
+    pub fn draw(&self) {
+        if self.exclaim > 0.0 {
+            let size = self.texture.size() * SCALE;
+            draw_texture_ex(
+                &self.texture,
+                self.spotter.x - size.x / 2.0,
+                self.spotter.y - size.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(size),
+                    ..Default::default()
+                },
+            );
+        }
+
+        if self.settings.debug && self.is_active() {
+            draw_circle_lines(self.last_known.x, self.last_known.y, 16.0, 2.0, RED);
+        }
+    }
+}
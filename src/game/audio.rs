@@ -0,0 +1,211 @@
+use macroquad::audio::{
+    load_sound, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound,
+};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::game::Settings;
+
+// Sound effects are every wav in assets/sounds, played by file name.
+// Music comes from a file per floor: a track for the floor, tracks for
+// rooms that have their own, and one for alerts. Changing tracks fades
+// one out as the next comes in.
+#[derive(Debug, Clone)]
+pub struct Audio {
+    settings: Settings,
+    sounds: HashMap<String, Sound>,
+    tracks: HashMap<String, Sound>,
+    floor: Option<String>,
+    rooms: HashMap<String, String>,
+    alert: Option<String>,
+    // Track coming in and the ones on their way out, with their volumes
+    current: Option<(String, f32)>,
+    fading: Vec<(String, f32)>,
+}
+
+const SOUND_DIR: &str = "assets/sounds";
+const MUSIC_DIR: &str = "assets/music";
+const FADE_TIME: f32 = 1.0;
+
+impl Audio {
+    pub async fn new(settings: Settings, music_file: &str) -> Self {
+        let mut audio = Self {
+            settings,
+            sounds: HashMap::new(),
+            tracks: HashMap::new(),
+            floor: None,
+            rooms: HashMap::new(),
+            alert: None,
+            current: None,
+            fading: Vec::new(),
+        };
+
+        let mut paths: Vec<_> = match fs::read_dir(SOUND_DIR) {
+            Ok(entries) => entries.flatten().map(|entry| entry.path()).collect(),
+            Err(_) => {
+                eprintln!("No sound folder: {}", SOUND_DIR);
+                Vec::new()
+            }
+        };
+        paths.sort();
+        for path in paths {
+            if path.extension().is_some_and(|extension| extension == "wav") {
+                if let Some(sound) = Audio::load(&path).await {
+                    let name = path.file_stem().unwrap().to_string_lossy().to_string();
+                    audio.sounds.insert(name, sound);
+                }
+            }
+        }
+
+        audio.load_music(music_file);
+        let mut names: Vec<String> = audio.rooms.values().cloned().collect();
+        names.extend(audio.floor.clone());
+        names.extend(audio.alert.clone());
+        names.sort();
+        names.dedup();
+        for name in names {
+            let path = Path::new(MUSIC_DIR).join(format!("{}.wav", name));
+            if let Some(track) = Audio::load(&path).await {
+                audio.tracks.insert(name, track);
+            }
+        }
+
+        audio
+    }
+
+    // Picks the track for the room, or the alert one while it's on
+    pub fn update(&mut self, delta_time: f32, room: &str, alert: bool) {
+        let wanted = alert
+            .then_some(self.alert.as_ref())
+            .flatten()
+            .or_else(|| self.rooms.get(room))
+            .or(self.floor.as_ref())
+            .cloned();
+        if wanted.as_ref() != self.current.as_ref().map(|(name, _)| name) {
+            self.switch(wanted);
+        }
+
+        let step = delta_time / FADE_TIME;
+        if let Some((_, volume)) = self.current.as_mut() {
+            *volume = (*volume + step).min(1.0);
+        }
+        for (_, volume) in self.fading.iter_mut() {
+            *volume -= step;
+        }
+        for (name, _) in self.fading.iter().filter(|(_, volume)| *volume <= 0.0) {
+            if let Some(track) = self.tracks.get(name) {
+                stop_sound(track);
+            }
+        }
+        self.fading.retain(|(_, volume)| *volume > 0.0);
+
+        self.set_volumes();
+    }
+
+    // Every frame on every screen, so the volume keys work in menus too
+    pub fn update_volume(&mut self) {
+        self.settings.update();
+        self.set_volumes();
+    }
+
+    pub fn play(&self, name: &str) {
+        match self.sounds.get(name) {
+            Some(sound) => play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: false,
+                    volume: self.settings.sound_volume,
+                },
+            ),
+            None => eprintln!("No sound: {}", name),
+        }
+    }
+
+    // Private
+    async fn load(path: &Path) -> Option<Sound> {
+        match load_sound(&path.to_string_lossy()).await {
+            Ok(sound) => Some(sound),
+            Err(error) => {
+                eprintln!("Couldn't load sound {}: {:?}", path.display(), error);
+                None
+            }
+        }
+    }
+
+    fn set_volumes(&self) {
+        let playing = self.current.iter().chain(self.fading.iter());
+        for (name, volume) in playing {
+            if let Some(track) = self.tracks.get(name) {
+                set_sound_volume(track, volume * self.settings.music_volume);
+            }
+        }
+    }
+
+    fn load_music(&mut self, music_file: &str) {
+        let source = match fs::read_to_string(music_file) {
+            Ok(source) => source,
+            Err(_) => {
+                eprintln!("No music file: {}", music_file);
+                return;
+            }
+        };
+
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => {
+                    eprintln!("Bad music line: {}", line);
+                    continue;
+                }
+            };
+
+            match key {
+                "floor" => self.floor = Some(value.to_string()),
+                "alert" => self.alert = Some(value.to_string()),
+                "room" => match value.split_once(';') {
+                    Some((room, track)) => {
+                        self.rooms
+                            .insert(room.trim().to_string(), track.trim().to_string());
+                    }
+                    None => eprintln!("Bad music line: {}", line),
+                },
+                _ => eprintln!("Bad music line: {}", line),
+            }
+        }
+    }
+
+    // Back before it's faded out, a track carries on from where it is
+    fn switch(&mut self, wanted: Option<String>) {
+        if let Some(current) = self.current.take() {
+            self.fading.push(current);
+        }
+
+        let name = match wanted {
+            Some(name) => name,
+            None => return,
+        };
+        let volume = match self.fading.iter().position(|(track, _)| *track == name) {
+            Some(index) => self.fading.remove(index).1,
+            None => {
+                if let Some(track) = self.tracks.get(&name) {
+                    play_sound(
+                        track,
+                        PlaySoundParams {
+                            looped: true,
+                            volume: 0.0,
+                        },
+                    );
+                }
+                0.0
+            }
+        };
+        self.current = Some((name, volume));
+    }
+}
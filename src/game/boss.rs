@@ -0,0 +1,525 @@
+use macroquad::prelude::*;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::time::Duration;
+
+use crate::game::Settings;
+use crate::game::{Anchor, Ui};
+use crate::game::{Blast, Bullet, Projectile};
+
+use super::equipmenu::{Item, Weapon};
+
+// Boss files live in assets/bosses, one per boss, as key: value lines.
+// Each phase line starts at a fraction of health and loops its steps:
+// phase: from; action [count] seconds, action [count] seconds, ...
+#[derive(Debug, Clone)]
+pub struct BossConfig {
+    pub name: String,
+    sheet: String,
+    frame: Rect,
+    frame_step: f32,
+    frame_count: u32,
+    health: f32,
+    speed: f32,
+    shot: Projectile,
+    weakness: Vec<Weapon>,
+    reward: Option<Item>,
+    phases: Vec<Phase>,
+}
+
+#[derive(Debug, Clone)]
+struct Phase {
+    from: f32,
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    action: Action,
+    count: u32,
+    time: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Action {
+    Wait,
+    Aim,
+    Spread,
+    Ring,
+    Lob,
+    Missile,
+    Flame,
+    Boomerang,
+    Strafe,
+    Charge,
+}
+
+#[derive(Debug, Clone)]
+pub struct Boss {
+    settings: Settings,
+    texture: Texture2D,
+    config: BossConfig,
+    frame_counter: u32,
+    frame_delay: Duration,
+    last_frame_update: std::time::Instant,
+    state: State,
+    phase: usize,
+    step: usize,
+    timer: f32,
+    fired: bool,
+    strafe: f32,
+    reward: Option<Item>,
+    pub room: String,
+    pub position: Vec2,
+    pub bounds: Rect,
+    pub collider: Rect,
+    pub health: f32,
+    pub bullets: Vec<Bullet>,
+    pub blasts: Vec<Blast>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    Waiting,
+    Fighting,
+    Defeated,
+}
+
+// Conts
+const SCALE: f32 = 4.0;
+const SHUTTER: u64 = 224;
+const RESISTED: f32 = 0.25;
+const SPREAD_ANGLE: f32 = 0.8;
+const FLAME_ANGLE: f32 = 0.25;
+const BAR_HEIGHT: f32 = 16.0;
+const BAR_MARGIN: f32 = 64.0;
+// Share of the screen width
+const BAR_PERCENT: f32 = 40.0;
+
+impl BossConfig {
+    pub fn load(path: &str) -> Option<Self> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(_) => {
+                eprintln!("No boss file: {}", path);
+                return None;
+            }
+        };
+        let reader = BufReader::new(file);
+
+        let mut config = BossConfig {
+            name: String::new(),
+            sheet: String::new(),
+            frame: Rect::new(0.0, 0.0, 16.0, 32.0),
+            frame_step: 0.0,
+            frame_count: 1,
+            health: 1.0,
+            speed: 0.0,
+            shot: Projectile::Pellet,
+            weakness: Vec::new(),
+            reward: None,
+            phases: Vec::new(),
+        };
+
+        for line in reader.lines() {
+            let line = line.unwrap();
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => {
+                    eprintln!("Bad boss line: {}", line);
+                    continue;
+                }
+            };
+            let numbers: Vec<f32> = value
+                .split(',')
+                .filter_map(|s| s.trim().parse::<f32>().ok())
+                .collect();
+
+            match key {
+                "name" => config.name = value.to_string(),
+                "sheet" => config.sheet = value.to_string(),
+                // x, y, width, height of the first frame, step to the next, frame count
+                "frame" if numbers.len() == 6 => {
+                    config.frame = Rect::new(numbers[0], numbers[1], numbers[2], numbers[3]);
+                    config.frame_step = numbers[4];
+                    config.frame_count = (numbers[5] as u32).max(1);
+                }
+                "health" if numbers.len() == 1 => config.health = numbers[0],
+                "speed" if numbers.len() == 1 => config.speed = numbers[0],
+                "shot" => match Projectile::from_name(value) {
+                    Some(shot) => config.shot = shot,
+                    None => eprintln!("Unknown boss shot: {}", value),
+                },
+                "weakness" => {
+                    for name in value.split(',').map(|s| s.trim()) {
+                        match Weapon::from_name(name) {
+                            Some(weapon) => config.weakness.push(weapon),
+                            None => eprintln!("Unknown weakness: {}", name),
+                        }
+                    }
+                }
+                "reward" => {
+                    config.reward = Item::from_name(value);
+                    if config.reward.is_none() {
+                        eprintln!("Unknown reward: {}", value);
+                    }
+                }
+                "phase" => match Phase::parse(value) {
+                    Some(phase) => config.phases.push(phase),
+                    None => eprintln!("Bad boss phase: {}", value),
+                },
+                _ => eprintln!("Bad boss line: {}", line),
+            }
+        }
+
+        // Highest fraction first, so the first phase is the opening one
+        config
+            .phases
+            .sort_by(|a, b| b.from.partial_cmp(&a.from).unwrap());
+
+        Some(config)
+    }
+}
+
+impl Phase {
+    fn parse(value: &str) -> Option<Self> {
+        let (from, steps) = value.split_once(';')?;
+        let from = from.trim().parse::<f32>().ok()?;
+
+        let mut parsed = Vec::new();
+        for step in steps.split(',') {
+            let words: Vec<&str> = step.split_whitespace().collect();
+            let action = match words.first().copied()? {
+                "wait" => Action::Wait,
+                "aim" => Action::Aim,
+                "spread" => Action::Spread,
+                "ring" => Action::Ring,
+                "lob" => Action::Lob,
+                "missile" => Action::Missile,
+                "flame" => Action::Flame,
+                "boomerang" => Action::Boomerang,
+                "strafe" => Action::Strafe,
+                "charge" => Action::Charge,
+                _ => return None,
+            };
+            let (count, time) = match words.len() {
+                2 => (1, words[1].parse::<f32>().ok()?),
+                3 => (words[1].parse::<u32>().ok()?, words[2].parse::<f32>().ok()?),
+                _ => return None,
+            };
+            parsed.push(Step {
+                action,
+                count,
+                time,
+            });
+        }
+
+        if parsed.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            from,
+            steps: parsed,
+        })
+    }
+}
+
+impl Boss {
+    // Public
+    pub async fn new(settings: Settings, name: &str, position: Vec2, room: &str) -> Option<Self> {
+        let config = BossConfig::load(&format!("assets/bosses/{}.txt", name))?;
+
+        // Load Textures
+        let texture = match load_texture(&config.sheet).await {
+            Ok(texture) => texture,
+            Err(_) => {
+                eprintln!("No boss sheet: {}", config.sheet);
+                return None;
+            }
+        };
+        texture.set_filter(FilterMode::Nearest);
+
+        let size = config.frame.size() * SCALE * 0.8;
+        let collider = Rect::new(position.x, position.y + size.y * 0.5, size.x, size.y * 0.5);
+
+        Some(Self {
+            settings,
+            texture,
+            frame_counter: 0,
+            frame_delay: Duration::from_millis(SHUTTER),
+            last_frame_update: std::time::Instant::now(),
+            state: State::Waiting,
+            phase: 0,
+            step: 0,
+            timer: 0.0,
+            fired: false,
+            strafe: 1.0,
+            reward: config.reward,
+            room: room.to_string(),
+            position,
+            bounds: Rect::new(position.x, position.y, size.x, size.y),
+            collider,
+            health: config.health,
+            bullets: Vec::new(),
+            blasts: Vec::new(),
+            config,
+        })
+    }
+
+    pub async fn update(&mut self, delta_time: f32, target: Vec2, colliders: &[Rect]) {
+        self.settings.update();
+
+        for bullet in self.bullets.iter_mut() {
+            bullet.update(delta_time, 0.0);
+            bullet.steer(target, delta_time);
+        }
+        self.blasts.extend(
+            self.bullets
+                .iter_mut()
+                .filter_map(|bullet| bullet.blast.take()),
+        );
+        self.bullets.retain(|bullet| bullet.alive);
+
+        if self.state != State::Fighting {
+            return;
+        }
+
+        self.update_phase();
+
+        let step = match self.current_step() {
+            Some(step) => step,
+            None => return,
+        };
+
+        if !self.fired {
+            self.fired = true;
+            self.fire(step, target).await;
+        }
+
+        match step.action {
+            Action::Strafe => {
+                let delta = Vec2::new(self.strafe * self.config.speed * delta_time, 0.0);
+                if !self.try_move(delta, colliders) {
+                    self.strafe = -self.strafe;
+                }
+                self.update_frame_counter();
+            }
+            Action::Charge => {
+                let to = target - self.collider.center();
+                if to.length() > 0.0 {
+                    let delta = to.normalize() * self.config.speed * delta_time;
+                    self.try_move(Vec2::new(delta.x, 0.0), colliders);
+                    self.try_move(Vec2::new(0.0, delta.y), colliders);
+                }
+                self.update_frame_counter();
+            }
+            _ => {}
+        }
+
+        self.timer += delta_time;
+        if self.timer >= step.time {
+            self.timer = 0.0;
+            self.fired = false;
+            self.step += 1;
+        }
+    }
+
+    pub fn draw(&self) {
+        for bullet in &self.bullets {
+            bullet.draw();
+        }
+
+        if self.state == State::Defeated {
+            return;
+        }
+
+        let frame = self.config.frame;
+        let src_rect = Rect::new(
+            frame.x + self.config.frame_step * self.frame_counter as f32,
+            frame.y,
+            frame.w,
+            frame.h,
+        );
+
+        if self.settings.debug {
+            draw_rectangle(
+                self.collider.x,
+                self.collider.y,
+                self.collider.w,
+                self.collider.h,
+                Color::new(1.0, 0.5, 0.0, 0.5),
+            );
+        }
+
+        draw_texture_ex(
+            &self.texture,
+            self.bounds.x,
+            self.bounds.y,
+            WHITE,
+            DrawTextureParams {
+                source: Some(src_rect),
+                dest_size: Some(self.bounds.size()),
+                ..Default::default()
+            },
+        );
+    }
+
+    // Across the top of the screen while the fight is on
+    pub fn draw_health_bar(&self, ui: &Ui) {
+        if self.state != State::Fighting {
+            return;
+        }
+
+        let size = vec2(ui.percent(BAR_PERCENT, 0.0).x, BAR_HEIGHT);
+        let bar = ui.rect(Anchor::Top, vec2(0.0, BAR_MARGIN), size);
+        let fill = (self.health / self.config.health).clamp(0.0, 1.0);
+
+        draw_rectangle(bar.x, bar.y, bar.w, bar.h, Color::new(0.0, 0.0, 0.0, 0.6));
+        draw_rectangle(bar.x, bar.y, bar.w * fill, bar.h, RED);
+        draw_rectangle_lines(bar.x, bar.y, bar.w, bar.h, ui.px(2.0), WHITE);
+        ui.text(
+            &self.config.name,
+            vec2(bar.x, bar.y - ui.px(4.0)),
+            24.0,
+            WHITE,
+        );
+    }
+
+    pub fn engage(&mut self) {
+        if self.state == State::Waiting {
+            self.state = State::Fighting;
+        }
+    }
+
+    // Weapons on the weakness list do full damage, everything else barely scratches
+    pub fn hit(&mut self, damage: f32, weapon: Weapon) {
+        if self.state == State::Defeated {
+            return;
+        }
+        self.engage();
+
+        let weak = self.config.weakness.is_empty() || self.config.weakness.contains(&weapon);
+        self.health -= if weak { damage } else { damage * RESISTED };
+
+        if self.health <= 0.0 {
+            self.health = 0.0;
+            self.state = State::Defeated;
+            self.bullets.clear();
+        }
+    }
+
+    pub fn is_fighting(&self) -> bool {
+        self.state == State::Fighting
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.state == State::Defeated
+    }
+
+    // Set once the boss goes down, for whatever waits on it
+    pub fn defeated_flag(&self) -> String {
+        format!("defeated {}", self.config.name.to_lowercase())
+    }
+
+    // Already beaten in a loaded game, the reward went with it
+    pub fn defeat(&mut self) {
+        self.health = 0.0;
+        self.state = State::Defeated;
+        self.reward = None;
+        self.bullets.clear();
+    }
+
+    // Handed over once, after the boss goes down
+    pub fn take_reward(&mut self) -> Option<Item> {
+        if self.state != State::Defeated {
+            return None;
+        }
+
+        self.reward.take()
+    }
+
+    // Private
+    fn update_phase(&mut self) {
+        let fraction = self.health / self.config.health;
+        let phase = self
+            .config
+            .phases
+            .iter()
+            .rposition(|phase| fraction <= phase.from)
+            .unwrap_or(0);
+
+        if phase != self.phase {
+            self.phase = phase;
+            self.step = 0;
+            self.timer = 0.0;
+            self.fired = false;
+        }
+    }
+
+    fn current_step(&self) -> Option<Step> {
+        let steps = &self.config.phases.get(self.phase)?.steps;
+        Some(steps[self.step % steps.len()])
+    }
+
+    async fn fire(&mut self, step: Step, target: Vec2) {
+        let origin = self.collider.center();
+        let aim = (target - origin).normalize_or_zero();
+        let aim = if aim == Vec2::ZERO { Vec2::Y } else { aim };
+
+        let (projectile, directions) = match step.action {
+            Action::Aim => (self.config.shot, vec![aim]),
+            Action::Spread => (
+                self.config.shot,
+                Bullet::spread(aim, step.count, SPREAD_ANGLE),
+            ),
+            Action::Ring => (self.config.shot, Bullet::ring(step.count)),
+            Action::Lob => (Projectile::Grenade, vec![aim; step.count as usize]),
+            Action::Missile => (
+                Projectile::Missile,
+                Bullet::spread(aim, step.count, SPREAD_ANGLE),
+            ),
+            // A tight fan of fire whatever the boss shoots otherwise
+            Action::Flame => (
+                Projectile::Flame,
+                Bullet::spread(aim, step.count, FLAME_ANGLE),
+            ),
+            Action::Boomerang => (
+                Projectile::Boomerang,
+                Bullet::spread(aim, step.count, SPREAD_ANGLE),
+            ),
+            Action::Wait | Action::Strafe | Action::Charge => return,
+        };
+
+        for direction in directions {
+            self.bullets
+                .push(Bullet::new(projectile, origin, direction, target).await);
+        }
+    }
+
+    fn try_move(&mut self, delta: Vec2, colliders: &[Rect]) -> bool {
+        let moved = self.collider.offset(delta);
+        if colliders.iter().any(|collider| collider.overlaps(&moved)) {
+            return false;
+        }
+
+        self.position += delta;
+        self.bounds = self.bounds.offset(delta);
+        self.collider = moved;
+        true
+    }
+
+    fn update_frame_counter(&mut self) {
+        let now = std::time::Instant::now();
+        let elapsed = now - self.last_frame_update;
+        if elapsed >= self.frame_delay {
+            self.last_frame_update = now;
+            self.frame_counter = (self.frame_counter + 1) % self.config.frame_count;
+        }
+    }
+}
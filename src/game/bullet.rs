@@ -1,71 +1,477 @@
-use std::time::Duration;
+use std::f32::consts::{PI, TAU};
 
 use macroquad::prelude::*;
 
+use super::equipmenu::Weapon;
+
+// Anything fired, thrown or put down. How it flies is its motion, what
+// happens when it runs out or hits something is its expiry.
 #[derive(Debug, Clone)]
 pub struct Bullet {
     texture: Texture2D,
+    source: Option<Rect>,
+    shadow: Option<Texture2D>,
     pub alive: bool,
+    pub weapon: Weapon,
+    pub blast: Option<Blast>,
     position: Vec2,
     direction: Vec2,
     size: Vec2,
     speed: f32,
-    born: std::time::Instant,
+    height: f32,
+    motion: Motion,
+    expiry: Expiry,
+    // Seconds, counted only while it's updated so it holds in menus
+    life: Option<f32>,
+    age: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projectile {
+    Bullet,
+    Pellet,
+    Shell,
+    Grenade,
+    Missile,
+    Remote,
+    Charge,
+    Mine,
+    Flame,
+    Boomerang,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Motion {
+    Straight,
+    // Lobbed from start to target, highest half way
+    Arc {
+        start: Vec2,
+        target: Vec2,
+        peak: f32,
+    },
+    // Turns towards whatever it's steered at, radians a second
+    Homing {
+        turn: f32,
+    },
+    // Turned by the steering it's given, radians a second at full lock
+    Guided {
+        turn: f32,
+    },
+    // Out to reach from where it was thrown and back again over its life
+    Boomerang {
+        start: Vec2,
+        reach: f32,
+    },
+    // Stays where it was put
+    Still,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Expiry {
+    Vanish,
+    // Snake's health runs on a bigger scale than everyone else's
+    Explode {
+        radius: f32,
+        damage: f32,
+        player_damage: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Blast {
+    pub origin: Vec2,
+    pub radius: f32,
+    pub damage: f32,
+    pub player_damage: f32,
+    pub weapon: Weapon,
+}
+
+struct Spec {
+    texture: &'static str,
+    // Part of the texture to draw, all of it if none
+    source: Option<Rect>,
+    size: Vec2,
+    speed: f32,
+    life: u64,
+    expiry: Expiry,
 }
 
-const SCALE: f32 = 0.25;
-const SPEED: f32 = 512.0;
+const SCALE: f32 = 3.0;
+const SHADOW_SIZE: Vec2 = Vec2::new(15.0, 9.0);
+const ARC_PEAK: f32 = 96.0;
+const BOOMERANG_REACH: f32 = 320.0;
+// Radians a second
+const BOOMERANG_SPIN: f32 = 12.0;
 
-const LIFE: u64 = 2560;
+impl Projectile {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bullet" => Some(Projectile::Bullet),
+            "pellet" => Some(Projectile::Pellet),
+            "shell" => Some(Projectile::Shell),
+            "grenade" => Some(Projectile::Grenade),
+            "missile" => Some(Projectile::Missile),
+            "remote" => Some(Projectile::Remote),
+            "charge" => Some(Projectile::Charge),
+            "mine" => Some(Projectile::Mine),
+            "flame" => Some(Projectile::Flame),
+            "boomerang" => Some(Projectile::Boomerang),
+            _ => None,
+        }
+    }
+
+    fn spec(&self) -> Spec {
+        match self {
+            Projectile::Bullet => Spec {
+                texture: "assets/effects/bullet.png",
+                source: None,
+                size: Vec2::new(8.0, 8.0),
+                speed: 512.0,
+                life: 2560,
+                expiry: Expiry::Vanish,
+            },
+            Projectile::Pellet => Spec {
+                texture: "assets/effects/shotgunner_bullet_1.png",
+                source: None,
+                size: Vec2::new(6.0, 6.0) * SCALE,
+                speed: 420.0,
+                life: 1600,
+                expiry: Expiry::Vanish,
+            },
+            Projectile::Shell => Spec {
+                texture: "assets/effects/tank_bullet_2.png",
+                source: None,
+                size: Vec2::new(8.0, 8.0) * SCALE,
+                speed: 360.0,
+                life: 2400,
+                expiry: Expiry::Explode {
+                    radius: 64.0,
+                    damage: 8.0,
+                    player_damage: 32.0,
+                },
+            },
+            Projectile::Grenade => Spec {
+                texture: "assets/weapons/grenade.png",
+                source: None,
+                size: Vec2::new(10.0, 10.0) * SCALE,
+                speed: 320.0,
+                life: 4000,
+                expiry: Expiry::Explode {
+                    radius: 96.0,
+                    damage: 3.0,
+                    player_damage: 12.0,
+                },
+            },
+            Projectile::Missile => Spec {
+                texture: "assets/weapons/missile.png",
+                source: None,
+                size: Vec2::new(16.0, 7.0) * SCALE,
+                speed: 300.0,
+                life: 4000,
+                expiry: Expiry::Explode {
+                    radius: 80.0,
+                    damage: 3.0,
+                    player_damage: 12.0,
+                },
+            },
+            Projectile::Remote => Spec {
+                texture: "assets/weapons/missile.png",
+                source: None,
+                size: Vec2::new(16.0, 7.0) * SCALE,
+                speed: 200.0,
+                life: 8000,
+                expiry: Expiry::Explode {
+                    radius: 80.0,
+                    damage: 3.0,
+                    player_damage: 12.0,
+                },
+            },
+            Projectile::Charge => Spec {
+                texture: "assets/weapons/mortar.png",
+                source: None,
+                size: Vec2::new(14.0, 5.0) * SCALE,
+                speed: 0.0,
+                life: 3000,
+                expiry: Expiry::Explode {
+                    radius: 128.0,
+                    damage: 4.0,
+                    player_damage: 16.0,
+                },
+            },
+            Projectile::Mine => Spec {
+                texture: "assets/weapons/mine.png",
+                source: None,
+                size: Vec2::new(10.0, 10.0) * SCALE,
+                speed: 0.0,
+                life: 60000,
+                expiry: Expiry::Explode {
+                    radius: 96.0,
+                    damage: 4.0,
+                    player_damage: 16.0,
+                },
+            },
+            // Short lived, so the stream only reaches a little way
+            Projectile::Flame => Spec {
+                texture: "assets/effects/flame.png",
+                source: Some(Rect::new(0.0, 0.0, 15.0, 16.0)),
+                size: Vec2::new(15.0, 16.0) * SCALE,
+                speed: 280.0,
+                life: 600,
+                expiry: Expiry::Vanish,
+            },
+            Projectile::Boomerang => Spec {
+                texture: "assets/effects/boomerang.png",
+                source: Some(Rect::new(0.0, 1.0, 11.0, 9.0)),
+                size: Vec2::new(11.0, 9.0) * SCALE,
+                speed: 0.0,
+                life: 2400,
+                expiry: Expiry::Vanish,
+            },
+        }
+    }
+
+    fn motion(&self, position: Vec2, target: Vec2) -> Motion {
+        match self {
+            Projectile::Bullet | Projectile::Pellet | Projectile::Shell | Projectile::Flame => {
+                Motion::Straight
+            }
+            Projectile::Grenade => Motion::Arc {
+                start: position,
+                target,
+                peak: ARC_PEAK,
+            },
+            Projectile::Missile => Motion::Homing { turn: 2.5 },
+            Projectile::Remote => Motion::Guided { turn: 3.0 },
+            Projectile::Boomerang => Motion::Boomerang {
+                start: position,
+                reach: BOOMERANG_REACH,
+            },
+            Projectile::Charge | Projectile::Mine => Motion::Still,
+        }
+    }
+}
 
 impl Bullet {
-    pub async fn new(position: Vec2, direction: Vec2, size: Vec2) -> Self {
+    // Position is the centre, target only matters to lobbed projectiles
+    pub async fn new(
+        projectile: Projectile,
+        position: Vec2,
+        direction: Vec2,
+        target: Vec2,
+    ) -> Self {
+        let spec = projectile.spec();
+        let motion = projectile.motion(position, target);
+
         // Load Textures
-        let texture = load_texture("assets/effects/bullet.png").await.unwrap();
+        let texture = load_texture(spec.texture).await.unwrap();
         texture.set_filter(FilterMode::Nearest);
 
+        let shadow = match motion {
+            Motion::Arc { .. } => {
+                let shadow = load_texture("assets/effects/shadow.png").await.unwrap();
+                shadow.set_filter(FilterMode::Nearest);
+                Some(shadow)
+            }
+            _ => None,
+        };
+
         Self {
             texture,
+            source: spec.source,
+            shadow,
             alive: true,
+            weapon: Weapon::Empty,
+            blast: None,
             position,
             direction,
-            size,
-            speed: SPEED,
-            born: std::time::Instant::now(),
+            size: spec.size,
+            speed: spec.speed,
+            height: 0.0,
+            motion,
+            expiry: spec.expiry,
+            life: Some(spec.life as f32 / 1000.0),
+            age: 0.0,
+        }
+    }
+
+    // Directions fanned evenly across angle either side of aim
+    pub fn spread(aim: Vec2, count: u32, angle: f32) -> Vec<Vec2> {
+        let count = count.max(1);
+        (0..count)
+            .map(|i| {
+                let t = if count == 1 {
+                    0.5
+                } else {
+                    i as f32 / (count - 1) as f32
+                };
+                Vec2::from_angle((t - 0.5) * angle * 2.0).rotate(aim)
+            })
+            .collect()
+    }
+
+    // Directions all the way round
+    pub fn ring(count: u32) -> Vec<Vec2> {
+        let count = count.max(1);
+        (0..count)
+            .map(|i| Vec2::from_angle(TAU * i as f32 / count as f32))
+            .collect()
+    }
+
+    // Turn is the steering for guided projectiles, -1 left to 1 right
+    pub fn update(&mut self, delta_time: f32, turn: f32) {
+        if !self.alive {
+            return;
+        }
+
+        self.age += delta_time;
+
+        match self.motion {
+            Motion::Straight | Motion::Homing { .. } => {
+                self.position += self.direction * self.speed * delta_time;
+            }
+            Motion::Guided { turn: max_turn } => {
+                let angle = turn.clamp(-1.0, 1.0) * max_turn * delta_time;
+                self.direction = Vec2::from_angle(angle).rotate(self.direction);
+                self.position += self.direction * self.speed * delta_time;
+            }
+            Motion::Arc {
+                start,
+                target,
+                peak,
+            } => {
+                let flight = (start.distance(target) / self.speed).max(0.01);
+                let t = (self.age / flight).min(1.0);
+                self.position = start.lerp(target, t);
+                self.height = 4.0 * peak * t * (1.0 - t);
+
+                if t >= 1.0 {
+                    self.expire();
+                    return;
+                }
+            }
+            Motion::Boomerang { start, reach } => {
+                let t = (self.age / self.life.unwrap_or(1.0)).min(1.0);
+                self.position = start + self.direction * reach * (PI * t).sin();
+            }
+            Motion::Still => {}
+        }
+
+        if self.life.is_some_and(|life| self.age > life) {
+            self.expire();
         }
     }
 
-    pub fn update(&mut self, delta_time: f32) {
-        let elapsed = std::time::Instant::now() - self.born;
+    // Homing projectiles turn towards the target, the rest ignore it
+    pub fn steer(&mut self, target: Vec2, delta_time: f32) {
+        if let Motion::Homing { turn } = self.motion {
+            let wanted = (target - self.position).normalize_or_zero();
+            if wanted == Vec2::ZERO {
+                return;
+            }
 
-        if elapsed > Duration::from_millis(LIFE) {
-            self.alive = false;
+            let max_turn = turn * delta_time;
+            let angle = self
+                .direction
+                .angle_between(wanted)
+                .clamp(-max_turn, max_turn);
+            self.direction = Vec2::from_angle(angle).rotate(self.direction);
         }
+    }
+
+    // Spent, by time or by hitting something. Explosives leave a blast behind.
+    pub fn expire(&mut self) {
+        if !self.alive {
+            return;
+        }
+        self.alive = false;
 
-        if self.alive {
-            self.position.x += self.direction.x * self.speed * delta_time;
-            self.position.y += self.direction.y * self.speed * delta_time;
+        if let Expiry::Explode {
+            radius,
+            damage,
+            player_damage,
+        } = self.expiry
+        {
+            self.blast = Some(Blast {
+                origin: self.position,
+                radius,
+                damage,
+                player_damage,
+                weapon: self.weapon,
+            });
         }
     }
 
-    pub fn draw(&self) {
-        if self.alive {
-            let src_rect = Rect::new(0.0, 0.0, self.size.x * SCALE, self.size.y * SCALE);
+    // Left lying around until something sets it off
+    pub fn arm(&mut self) {
+        self.life = None;
+    }
+
+    // Lobbed projectiles fly over everything until they land
+    pub fn is_airborne(&self) -> bool {
+        matches!(self.motion, Motion::Arc { .. })
+    }
+
+    pub fn is_placed(&self) -> bool {
+        self.motion == Motion::Still
+    }
+
+    pub fn explodes(&self) -> bool {
+        matches!(self.expiry, Expiry::Explode { .. })
+    }
 
-            // Set dest
-            let bounds = Rect::new(self.position.x, self.position.y, src_rect.w, src_rect.h);
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
 
+    pub fn bounds(&self) -> Rect {
+        Rect::new(
+            self.position.x - self.size.x / 2.0,
+            self.position.y - self.size.y / 2.0,
+            self.size.x,
+            self.size.y,
+        )
+    }
+
+    pub fn draw(&self) {
+        if !self.alive {
+            return;
+        }
+
+        if let Some(shadow) = &self.shadow {
+            let size = SHADOW_SIZE * SCALE;
             draw_texture_ex(
-                &self.texture,
-                bounds.x,
-                bounds.y,
+                shadow,
+                self.position.x - size.x / 2.0,
+                self.position.y - size.y / 2.0,
                 WHITE,
                 DrawTextureParams {
-                    source: Some(src_rect),
-                    dest_size: Some(bounds.size()),
+                    dest_size: Some(size),
                     ..Default::default()
                 },
             );
         }
+
+        // Sprites face right, turn them the way they're going
+        let rotation = match self.motion {
+            Motion::Homing { .. } | Motion::Guided { .. } => {
+                self.direction.y.atan2(self.direction.x)
+            }
+            Motion::Boomerang { .. } => self.age * BOOMERANG_SPIN,
+            _ => 0.0,
+        };
+
+        let bounds = self.bounds();
+        draw_texture_ex(
+            &self.texture,
+            bounds.x,
+            bounds.y - self.height,
+            WHITE,
+            DrawTextureParams {
+                source: self.source,
+                dest_size: Some(bounds.size()),
+                rotation,
+                ..Default::default()
+            },
+        );
     }
 }
@@ -0,0 +1,713 @@
+use macroquad::prelude::*;
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use crate::game::Settings;
+use crate::game::{Anchor, Ui};
+
+// Codec files live in assets/codec, one per floor, as key: value lines.
+// Contacts sit on a frequency, calls on a frequency are heard once their
+// room or flag comes up and the says after each call are the conversation.
+#[derive(Debug, Clone)]
+pub struct Codec {
+    settings: Settings,
+    contacts: Vec<Contact>,
+    calls: Vec<Call>,
+    heard: HashSet<usize>,
+    snake: Portrait,
+    state: State,
+    incoming: Option<usize>,
+    // Contact on the other end of the call
+    caller: Option<usize>,
+    // Scripted conversations close the codec when they're done
+    scripted: bool,
+    // In hundredths, 120.85 is 12085
+    frequency: u32,
+    hold_timer: f32,
+    typed: f32,
+}
+
+#[derive(Debug, Clone)]
+struct Portrait {
+    texture: Texture2D,
+    source: Rect,
+}
+
+#[derive(Debug, Clone)]
+struct Contact {
+    frequency: Option<u32>,
+    name: String,
+    portrait: Portrait,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Trigger {
+    Any,
+    Room(String),
+    Flag(String),
+}
+
+#[derive(Debug, Clone)]
+struct Call {
+    frequency: u32,
+    trigger: Trigger,
+    incoming: bool,
+    says: Vec<Say>,
+}
+
+#[derive(Debug, Clone)]
+struct Say {
+    speaker: String,
+    text: String,
+}
+
+// As much of a say as fits in the box at once
+#[derive(Debug, Clone)]
+struct Page {
+    speaker: String,
+    rows: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum State {
+    Closed,
+    Dial,
+    // No call means nobody picked up
+    Talking {
+        call: Option<usize>,
+        pages: Vec<Page>,
+        page: usize,
+    },
+}
+
+// Conts
+const FREQUENCY_MIN: u32 = 12000;
+const FREQUENCY_MAX: u32 = 12099;
+const FREQUENCY_START: u32 = 12085;
+// Holding a direction starts tuning on its own after a moment
+const HOLD_DELAY: f32 = 0.4;
+const TUNE_RATE: f32 = 20.0;
+const TYPE_RATE: f32 = 40.0;
+
+// Layout
+const SHADE: Color = Color::new(0.0, 0.0, 0.0, 0.9);
+const CODEC_GREEN: Color = Color::new(0.3, 0.9, 0.5, 1.0);
+const DIM: Color = Color::new(0.4, 0.4, 0.4, 1.0);
+const FONT_SIZE: f32 = 28.0;
+const PORTRAIT_SIZE: Vec2 = Vec2::new(160.0, 288.0);
+const PORTRAIT_OFFSET: Vec2 = Vec2::new(160.0, 64.0);
+const DIAL_SIZE: Vec2 = Vec2::new(320.0, 80.0);
+const DIAL_OFFSET: Vec2 = Vec2::new(0.0, 128.0);
+const TUNER_HEIGHT: f32 = 8.0;
+const TEXT_BOX_SIZE: Vec2 = Vec2::new(960.0, 200.0);
+const TEXT_BOX_OFFSET: Vec2 = Vec2::new(0.0, 48.0);
+const TEXT_MARGIN: f32 = 24.0;
+const PAGE_ROWS: usize = 3;
+// Halfway down the right edge, clear of the HUD and the alert bar
+const RING_OFFSET: Vec2 = Vec2::new(24.0, 0.0);
+
+impl Codec {
+    pub async fn new(settings: Settings, codec_file: &str) -> Self {
+        let snake_texture = load_texture("assets/snake/standing.png").await.unwrap();
+        snake_texture.set_filter(FilterMode::Nearest);
+
+        let mut codec = Self {
+            settings,
+            contacts: Vec::new(),
+            calls: Vec::new(),
+            heard: HashSet::new(),
+            snake: Portrait {
+                texture: snake_texture,
+                source: Rect::new(0.0, 0.0, 17.0, 30.0),
+            },
+            state: State::Closed,
+            incoming: None,
+            caller: None,
+            scripted: false,
+            frequency: FREQUENCY_START,
+            hold_timer: 0.0,
+            typed: 0.0,
+        };
+
+        let file = match File::open(codec_file) {
+            Ok(file) => file,
+            Err(_) => {
+                eprintln!("No codec file: {}", codec_file);
+                return codec;
+            }
+        };
+        let reader = BufReader::new(file);
+
+        for line in reader.lines() {
+            let line = line.unwrap();
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => {
+                    eprintln!("Bad codec line: {}", line);
+                    continue;
+                }
+            };
+            let fields: Vec<&str> = value.split(';').map(|s| s.trim()).collect();
+
+            match key {
+                // Faces are for people off the dial that scripts have talk
+                "contact" | "face" => {
+                    let (frequency, fields) = match key {
+                        "contact" => (parse_frequency(fields[0]), &fields[1..]),
+                        _ => (None, &fields[..]),
+                    };
+                    if fields.len() != 3 || (key == "contact" && frequency.is_none()) {
+                        eprintln!("Bad codec {}: {}", key, value);
+                        continue;
+                    }
+
+                    if let Some(portrait) = Portrait::load(fields[1], fields[2]).await {
+                        codec.contacts.push(Contact {
+                            frequency,
+                            name: fields[0].to_string(),
+                            portrait,
+                        });
+                    }
+                }
+                "call" if fields.len() >= 2 => {
+                    match (parse_frequency(fields[0]), Trigger::parse(fields[1])) {
+                        (Some(frequency), Some(trigger)) => codec.calls.push(Call {
+                            frequency,
+                            trigger,
+                            incoming: fields.get(2) == Some(&"incoming"),
+                            says: Vec::new(),
+                        }),
+                        _ => eprintln!("Bad codec call: {}", value),
+                    }
+                }
+                // Said in the last call opened
+                "say" if fields.len() >= 2 => match codec.calls.last_mut() {
+                    Some(call) => {
+                        let (speaker, text) = value.split_once(';').unwrap();
+                        call.says.push(Say {
+                            speaker: speaker.trim().to_string(),
+                            text: text.trim().to_string(),
+                        });
+                    }
+                    None => eprintln!("Codec say before any call: {}", value),
+                },
+                _ => eprintln!("Bad codec line: {}", line),
+            }
+        }
+
+        codec
+    }
+
+    pub fn is_open(&self) -> bool {
+        !matches!(self.state, State::Closed)
+    }
+
+    // Calls by their place in the file, for saves
+    pub fn heard(&self) -> Vec<usize> {
+        let mut heard: Vec<usize> = self.heard.iter().copied().collect();
+        heard.sort();
+        heard
+    }
+
+    pub fn restore(&mut self, heard: &[usize]) {
+        self.heard = heard
+            .iter()
+            .copied()
+            .filter(|index| *index < self.calls.len())
+            .collect();
+    }
+
+    // Starts ringing for the first incoming call that's come up,
+    // true the moment it does
+    pub fn ring(&mut self, room: &str, flags: &HashSet<String>) -> bool {
+        if self.incoming.is_some() || self.is_open() {
+            return false;
+        }
+
+        self.incoming = self
+            .calls
+            .iter()
+            .enumerate()
+            .find(|(index, call)| {
+                call.incoming && !self.heard.contains(index) && call.trigger.matches(room, flags)
+            })
+            .map(|(index, _)| index);
+        self.incoming.is_some()
+    }
+
+    pub fn update(&mut self, delta_time: f32, room: &str, flags: &HashSet<String>) {
+        self.settings.update();
+
+        match self.state {
+            State::Closed => {
+                if is_key_pressed(KeyCode::C) {
+                    match self.incoming {
+                        // Answering tunes straight in
+                        Some(index) => {
+                            self.frequency = self.calls[index].frequency;
+                            self.start_call(Some(index));
+                        }
+                        None => self.state = State::Dial,
+                    }
+                }
+            }
+            State::Dial => self.update_dial(delta_time, room, flags),
+            State::Talking { .. } => self.update_talking(delta_time),
+        }
+    }
+
+    pub fn draw(&self, ui: &Ui) {
+        if !self.is_open() {
+            self.draw_ringing(ui);
+            return;
+        }
+
+        let screen = ui.size();
+        draw_rectangle(0.0, 0.0, ui.px(screen.x), ui.px(screen.y), SHADE);
+
+        // Whoever's talking is lit, the other dimmed
+        let speaker = match &self.state {
+            State::Talking { pages, page, .. } => {
+                pages.get(*page).map(|page| page.speaker.as_str())
+            }
+            _ => None,
+        };
+        let contact = match self.state {
+            State::Talking { .. } => self.caller.map(|index| &self.contacts[index]),
+            _ => self.tuned_contact(),
+        };
+
+        let left = ui.rect(Anchor::TopLeft, PORTRAIT_OFFSET, PORTRAIT_SIZE);
+        let snake_lit = speaker.is_none_or(|speaker| speaker == "Snake");
+        self.draw_portrait(ui, left, Some(&self.snake), "Snake", snake_lit);
+
+        let right = ui.rect(Anchor::TopRight, PORTRAIT_OFFSET, PORTRAIT_SIZE);
+        match contact {
+            Some(contact) => {
+                let lit = speaker.is_none_or(|speaker| speaker == contact.name);
+                self.draw_portrait(ui, right, Some(&contact.portrait), &contact.name, lit);
+            }
+            None => self.draw_portrait(ui, right, None, "", false),
+        }
+
+        self.draw_dial(ui);
+        self.draw_text_box(ui, contact);
+    }
+
+    // A conversation from a script, tuned to whoever's on the other end
+    pub fn play(&mut self, says: &[(String, String)]) {
+        let says: Vec<Say> = says
+            .iter()
+            .map(|(speaker, text)| Say {
+                speaker: speaker.clone(),
+                text: text.clone(),
+            })
+            .collect();
+
+        let caller = self
+            .contacts
+            .iter()
+            .position(|contact| says.iter().any(|say| say.speaker == contact.name));
+        if let Some(frequency) = caller.and_then(|index| self.contacts[index].frequency) {
+            self.frequency = frequency;
+        }
+
+        let pages = says.iter().flat_map(Page::split).collect();
+        self.talk(None, caller, pages);
+        self.scripted = true;
+    }
+
+    // Private
+    fn update_dial(&mut self, delta_time: f32, room: &str, flags: &HashSet<String>) {
+        if is_key_pressed(KeyCode::C) || is_key_pressed(KeyCode::Backspace) {
+            self.state = State::Closed;
+            return;
+        }
+
+        let step = if is_key_down(KeyCode::Left) {
+            -1
+        } else if is_key_down(KeyCode::Right) {
+            1
+        } else {
+            0
+        };
+
+        self.hold_timer += delta_time;
+        if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::Right) {
+            self.hold_timer = 0.0;
+            self.tune(step);
+        } else if step != 0 && self.hold_timer >= HOLD_DELAY {
+            self.hold_timer -= 1.0 / TUNE_RATE;
+            self.tune(step);
+        }
+
+        if is_key_pressed(KeyCode::Enter) {
+            let call = self.find_call(room, flags);
+            self.start_call(call);
+        }
+    }
+
+    fn update_talking(&mut self, delta_time: f32) {
+        let (call, total, last_page) = match &self.state {
+            State::Talking { call, pages, page } => (
+                *call,
+                pages.get(*page).map_or(0, |page| page.len()),
+                *page + 1 >= pages.len(),
+            ),
+            _ => return,
+        };
+
+        // Hanging up counts as having heard it
+        if is_key_pressed(KeyCode::Backspace) || is_key_pressed(KeyCode::C) {
+            self.end_call(call);
+            return;
+        }
+
+        self.typed = (self.typed + TYPE_RATE * delta_time).min(total as f32);
+
+        // Enter finishes the page first, then turns it
+        if is_key_pressed(KeyCode::Enter) {
+            if (self.typed as usize) < total {
+                self.typed = total as f32;
+            } else if last_page {
+                self.end_call(call);
+            } else if let State::Talking { page, .. } = &mut self.state {
+                *page += 1;
+                self.typed = 0.0;
+            }
+        }
+    }
+
+    fn tune(&mut self, step: i32) {
+        let frequency = self.frequency as i32 + step;
+        self.frequency = frequency.clamp(FREQUENCY_MIN as i32, FREQUENCY_MAX as i32) as u32;
+    }
+
+    // Something new on the frequency if there is, otherwise the last thing said
+    fn find_call(&self, room: &str, flags: &HashSet<String>) -> Option<usize> {
+        let matching: Vec<usize> = self
+            .calls
+            .iter()
+            .enumerate()
+            .filter(|(_, call)| {
+                call.frequency == self.frequency && call.trigger.matches(room, flags)
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        matching
+            .iter()
+            .find(|index| !self.heard.contains(index))
+            .or(matching.last())
+            .copied()
+    }
+
+    fn start_call(&mut self, call: Option<usize>) {
+        if call.is_some() && call == self.incoming {
+            self.incoming = None;
+        }
+
+        let pages = match call {
+            Some(index) => self.calls[index]
+                .says
+                .iter()
+                .flat_map(Page::split)
+                .collect(),
+            None => vec![Page {
+                speaker: String::new(),
+                rows: vec!["No response.".to_string()],
+            }],
+        };
+        let caller = self
+            .contacts
+            .iter()
+            .position(|contact| contact.frequency == Some(self.frequency));
+        self.talk(call, caller, pages);
+    }
+
+    fn talk(&mut self, call: Option<usize>, caller: Option<usize>, pages: Vec<Page>) {
+        self.caller = caller;
+        self.scripted = false;
+        self.typed = 0.0;
+        self.state = State::Talking {
+            call,
+            pages,
+            page: 0,
+        };
+    }
+
+    fn end_call(&mut self, call: Option<usize>) {
+        if let Some(index) = call {
+            self.heard.insert(index);
+        }
+        self.state = if self.scripted {
+            State::Closed
+        } else {
+            State::Dial
+        };
+    }
+
+    fn tuned_contact(&self) -> Option<&Contact> {
+        self.contacts
+            .iter()
+            .find(|contact| contact.frequency == Some(self.frequency))
+    }
+
+    fn draw_ringing(&self, ui: &Ui) {
+        if self.incoming.is_none() || (get_time() * 2.0).fract() >= 0.5 {
+            return;
+        }
+
+        let text = "CALL (C)";
+        ui.label(text, Anchor::Right, RING_OFFSET, FONT_SIZE, CODEC_GREEN);
+    }
+
+    // Static when there's nobody on the line
+    fn draw_portrait(
+        &self,
+        ui: &Ui,
+        frame: Rect,
+        portrait: Option<&Portrait>,
+        name: &str,
+        lit: bool,
+    ) {
+        draw_rectangle(frame.x, frame.y, frame.w, frame.h, BLACK);
+
+        match portrait {
+            Some(portrait) => {
+                let scale = (frame.w / portrait.source.w).min(frame.h / portrait.source.h);
+                let size = portrait.source.size() * scale;
+                let position = frame.center() - size / 2.0;
+                draw_texture_ex(
+                    &portrait.texture,
+                    position.x,
+                    position.y,
+                    if lit { WHITE } else { DIM },
+                    DrawTextureParams {
+                        source: Some(portrait.source),
+                        dest_size: Some(size),
+                        ..Default::default()
+                    },
+                );
+            }
+            None => {
+                let cell = ui.px(8.0);
+                let mut y = frame.y;
+                while y < frame.bottom() {
+                    let mut x = frame.x;
+                    while x < frame.right() {
+                        let shade = rand::gen_range(0.1, 0.5);
+                        let w = cell.min(frame.right() - x);
+                        let h = cell.min(frame.bottom() - y);
+                        draw_rectangle(x, y, w, h, Color::new(shade, shade, shade, 1.0));
+                        x += cell;
+                    }
+                    y += cell;
+                }
+            }
+        }
+
+        draw_rectangle_lines(frame.x, frame.y, frame.w, frame.h, ui.px(4.0), CODEC_GREEN);
+        let width = ui.text_width(name, FONT_SIZE);
+        let position = vec2(
+            frame.center().x - width / 2.0,
+            frame.bottom() + ui.px(FONT_SIZE + 8.0),
+        );
+        ui.text(name, position, FONT_SIZE, CODEC_GREEN);
+    }
+
+    fn draw_dial(&self, ui: &Ui) {
+        let dial = ui.rect(Anchor::Top, DIAL_OFFSET, DIAL_SIZE);
+        draw_rectangle_lines(dial.x, dial.y, dial.w, dial.h, ui.px(4.0), CODEC_GREEN);
+
+        let text = format!("{}.{:02}", self.frequency / 100, self.frequency % 100);
+        let width = ui.text_width(&text, 64.0);
+        let position = vec2(dial.center().x - width / 2.0, dial.bottom() - ui.px(20.0));
+        ui.text(&text, position, 64.0, CODEC_GREEN);
+
+        // Where on the band the dial sits
+        let tuner = Rect::new(
+            dial.x,
+            dial.bottom() + ui.px(16.0),
+            dial.w,
+            ui.px(TUNER_HEIGHT),
+        );
+        draw_rectangle_lines(tuner.x, tuner.y, tuner.w, tuner.h, ui.px(2.0), DIM);
+        let fraction =
+            (self.frequency - FREQUENCY_MIN) as f32 / (FREQUENCY_MAX - FREQUENCY_MIN) as f32;
+        let marker = tuner.x + tuner.w * fraction;
+        draw_rectangle(
+            marker - ui.px(2.0),
+            tuner.y,
+            ui.px(4.0),
+            tuner.h,
+            CODEC_GREEN,
+        );
+
+        // Every contact on the band, to save hunting for them
+        if self.settings.debug {
+            let tuned = self
+                .contacts
+                .iter()
+                .filter_map(|contact| Some((contact.frequency?, &contact.name)));
+            for (row, (frequency, name)) in tuned.enumerate() {
+                let text = format!("{}.{:02} {}", frequency / 100, frequency % 100, name);
+                let y = tuner.bottom() + ui.px(FONT_SIZE * (row + 1) as f32);
+                ui.text(&text, vec2(tuner.x, y), FONT_SIZE * 0.75, DIM);
+            }
+        }
+    }
+
+    fn draw_text_box(&self, ui: &Ui, contact: Option<&Contact>) {
+        let text_box = ui.rect(Anchor::Bottom, TEXT_BOX_OFFSET, TEXT_BOX_SIZE);
+        draw_rectangle(text_box.x, text_box.y, text_box.w, text_box.h, BLACK);
+        draw_rectangle_lines(
+            text_box.x,
+            text_box.y,
+            text_box.w,
+            text_box.h,
+            ui.px(2.0),
+            CODEC_GREEN,
+        );
+
+        let margin = ui.px(TEXT_MARGIN);
+        let left = text_box.x + margin;
+        let row_height = ui.px(FONT_SIZE * 1.3);
+        let mut y = text_box.y + margin + ui.px(FONT_SIZE);
+
+        let hint = match &self.state {
+            State::Talking { pages, page, .. } => {
+                if let Some(page) = pages.get(*page) {
+                    ui.text(&page.speaker, vec2(left, y), FONT_SIZE, CODEC_GREEN);
+
+                    // Typed out a letter at a time
+                    let mut left_to_type = self.typed as usize;
+                    for row in &page.rows {
+                        y += row_height;
+                        let shown: String = row.chars().take(left_to_type).collect();
+                        left_to_type = left_to_type.saturating_sub(row.chars().count());
+                        ui.text(&shown, vec2(left, y), FONT_SIZE, WHITE);
+                    }
+                }
+                "Enter next   Backspace hang up"
+            }
+            _ => {
+                if let Some(contact) = contact {
+                    ui.text(&contact.name, vec2(left, y), FONT_SIZE, CODEC_GREEN);
+                }
+                "Left/Right tune   Enter call   C close"
+            }
+        };
+
+        let width = ui.text_width(hint, FONT_SIZE * 0.75);
+        let position = vec2(
+            text_box.right() - margin - width,
+            text_box.bottom() - margin,
+        );
+        ui.text(hint, position, FONT_SIZE * 0.75, DIM);
+    }
+}
+
+impl Portrait {
+    // Sheet path and the x, y, width, height of the face on it
+    async fn load(sheet: &str, source: &str) -> Option<Self> {
+        let numbers: Vec<f32> = source
+            .split(',')
+            .filter_map(|s| s.trim().parse::<f32>().ok())
+            .collect();
+        if numbers.len() != 4 {
+            eprintln!("Bad portrait: {}", source);
+            return None;
+        }
+
+        let texture = match load_texture(sheet).await {
+            Ok(texture) => texture,
+            Err(_) => {
+                eprintln!("No portrait: {}", sheet);
+                return None;
+            }
+        };
+        texture.set_filter(FilterMode::Nearest);
+
+        Some(Self {
+            texture,
+            source: Rect::new(numbers[0], numbers[1], numbers[2], numbers[3]),
+        })
+    }
+}
+
+impl Trigger {
+    fn parse(value: &str) -> Option<Self> {
+        if value == "any" {
+            return Some(Trigger::Any);
+        }
+
+        match value.split_once(' ')? {
+            ("room", room) => Some(Trigger::Room(room.trim().to_string())),
+            ("flag", flag) => Some(Trigger::Flag(flag.trim().to_string())),
+            _ => None,
+        }
+    }
+
+    fn matches(&self, room: &str, flags: &HashSet<String>) -> bool {
+        match self {
+            Trigger::Any => true,
+            Trigger::Room(name) => name == room,
+            Trigger::Flag(flag) => flags.contains(flag),
+        }
+    }
+}
+
+impl Page {
+    // Wrapped to the text box and cut into pages
+    fn split(say: &Say) -> Vec<Page> {
+        let width = TEXT_BOX_SIZE.x - TEXT_MARGIN * 2.0;
+        let mut rows = Vec::new();
+        let mut row = String::new();
+
+        for word in say.text.split_whitespace() {
+            let candidate = if row.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", row, word)
+            };
+
+            if !row.is_empty()
+                && measure_text(&candidate, None, FONT_SIZE as u16, 1.0).width > width
+            {
+                rows.push(std::mem::replace(&mut row, word.to_string()));
+            } else {
+                row = candidate;
+            }
+        }
+        if !row.is_empty() {
+            rows.push(row);
+        }
+
+        rows.chunks(PAGE_ROWS)
+            .map(|rows| Page {
+                speaker: say.speaker.clone(),
+                rows: rows.to_vec(),
+            })
+            .collect()
+    }
+
+    // Letters to type out
+    fn len(&self) -> usize {
+        self.rows.iter().map(|row| row.chars().count()).sum()
+    }
+}
+
+fn parse_frequency(value: &str) -> Option<u32> {
+    let frequency = (value.parse::<f32>().ok()? * 100.0).round() as u32;
+    (FREQUENCY_MIN..=FREQUENCY_MAX)
+        .contains(&frequency)
+        .then_some(frequency)
+}
@@ -4,6 +4,9 @@ use macroquad::prelude::*;
 pub struct ColliderMap {
     texture: Texture2D,
     pub colliders: Vec<Rect>,
+    solid: Vec<bool>,
+    width: usize,
+    height: usize,
     parent_bounds: Rect,
 }
 
@@ -15,9 +18,11 @@ impl ColliderMap {
         let texture = load_texture(path).await.unwrap();
 
         let mut colliders = Vec::new();
+        let mut solid = Vec::new();
         for y in 0..image.height() {
             for x in 0..image.width() {
                 let pixel = image.get_pixel(x as u32, y as u32);
+                solid.push(pixel == BLACK);
 
                 let mut n_pix = RED;
                 let mut s_pix = RED;
@@ -56,10 +61,50 @@ impl ColliderMap {
         ColliderMap {
             texture,
             colliders,
+            solid,
+            width: image.width(),
+            height: image.height(),
             parent_bounds,
         }
     }
 
+    pub fn is_solid(&self, point: Vec2) -> bool {
+        let local = point - self.parent_bounds.point();
+        if local.x < 0.0 || local.y < 0.0 {
+            return false;
+        }
+
+        let x = (local.x / SIZE) as usize;
+        let y = (local.y / SIZE) as usize;
+        if x >= self.width || y >= self.height {
+            return false;
+        }
+
+        self.solid[y * self.width + x]
+    }
+
+    // Count the separate stretches of wall a straight line passes through
+    pub fn walls_between(&self, from: Vec2, to: Vec2) -> u32 {
+        let steps = (from.distance(to) / (SIZE / 2.0)).ceil() as u32;
+
+        let mut walls = 0;
+        let mut inside = false;
+        for i in 0..=steps {
+            let t = if steps == 0 {
+                0.0
+            } else {
+                i as f32 / steps as f32
+            };
+            let solid = self.is_solid(from.lerp(to, t));
+            if solid && !inside {
+                walls += 1;
+            }
+            inside = solid;
+        }
+
+        walls
+    }
+
     pub fn draw(&self) {
         draw_texture_ex(
             &self.texture,
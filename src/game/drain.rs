@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+// Periodically takes from a value, like smoking or gas eating at health.
+// Only counts the frames it's ticked, so it stops with the game.
+#[derive(Debug, Clone)]
+pub struct Drain {
+    interval: Duration,
+    amount: f32,
+    floor: f32,
+    elapsed: Duration,
+}
+
+impl Drain {
+    pub fn new(interval: Duration, amount: f32, floor: f32) -> Self {
+        Self {
+            interval,
+            amount,
+            floor,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn tick(&mut self, delta_time: f32, value: &mut f32) {
+        self.elapsed += Duration::from_secs_f32(delta_time);
+        if self.elapsed >= self.interval {
+            if *value > self.floor {
+                *value = (*value - self.amount).max(self.floor);
+            }
+            self.elapsed = Duration::ZERO;
+        }
+    }
+
+    pub fn set_amount(&mut self, amount: f32) {
+        self.amount = amount;
+    }
+}
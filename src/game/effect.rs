@@ -1,12 +1,12 @@
 use macroquad::prelude::*;
 use std::time::Duration;
 
-use super::equipmenu::Item;
+use super::status::StatusKind;
 
+// Animation over Snake's head for whichever status shows one
 #[derive(Debug, Clone)]
 pub struct Effect {
-    texture: Texture2D,
-    // textures: [Texture2D; 1],
+    textures: [Texture2D; 2],
     frame_counter: u32,
     frame_delay: Duration,
     last_frame_update: std::time::Instant,
@@ -14,6 +14,11 @@ pub struct Effect {
 
 // Frame Size
 const FS_SMOKING: Vec2 = Vec2::new(14.0, 14.0);
+const FS_STUNNED: Vec2 = Vec2::new(14.0, 18.0);
+
+// Max Frames
+const MF_SMOKING: u32 = 2;
+const MF_STUNNED: u32 = 4;
 
 const SCALE: f32 = 2.0;
 const SHUTTER: u64 = 1000;
@@ -24,24 +29,26 @@ impl Effect {
         let smoking_texture = load_texture("assets/effects/smoking.png").await.unwrap();
         smoking_texture.set_filter(FilterMode::Nearest);
 
+        let stunned_texture = load_texture("assets/effects/sleep.png").await.unwrap();
+        stunned_texture.set_filter(FilterMode::Nearest);
+
         Self {
-            texture: smoking_texture.clone(),
-            // textures: [smoking_texture],
+            textures: [smoking_texture, stunned_texture],
             frame_counter: 0,
             frame_delay: Duration::from_millis(SHUTTER),
             last_frame_update: std::time::Instant::now(),
         }
     }
 
-    pub fn update(&mut self, index: usize) {
+    pub fn update(&mut self, kind: StatusKind) {
         let now = std::time::Instant::now();
         let elapsed = now - self.last_frame_update;
 
         // Get frame limits
-        let max_frames = match Item::from_index(index) {
-            Some(Item::Empty) => 0,
-            Some(Item::Cigs) => 2,
-            None => todo!(),
+        let max_frames = match kind {
+            StatusKind::Smoking => MF_SMOKING,
+            StatusKind::Stunned => MF_STUNNED,
+            _ => 1,
         };
 
         // Check frame vs time
@@ -52,15 +59,15 @@ impl Effect {
         }
     }
 
-    pub fn draw(&self, player_bounds: Rect, index: usize) {
+    pub fn draw(&self, player_bounds: Rect, kind: StatusKind) {
         // Set Src
-
-        let frame = (self.frame_counter % 2) as f32;
-        let src_rect: Rect = match Item::from_index(index) {
-            Some(Item::Empty) => Rect::new(0.0, 0.0, 0.0, 0.0),
-            Some(Item::Cigs) => Rect::new(FS_SMOKING.x * frame, 0.0, FS_SMOKING.x, FS_SMOKING.y),
-            None => todo!(),
+        let (texture, size, max_frames) = match kind {
+            StatusKind::Smoking => (&self.textures[0], FS_SMOKING, MF_SMOKING),
+            StatusKind::Stunned => (&self.textures[1], FS_STUNNED, MF_STUNNED),
+            _ => return,
         };
+        let frame = (self.frame_counter % max_frames) as f32;
+        let src_rect = Rect::new(size.x * frame, 0.0, size.x, size.y);
 
         // Set dest
         let bounds = Rect::new(
@@ -71,7 +78,7 @@ impl Effect {
         );
 
         draw_texture_ex(
-            &self.texture,
+            texture,
             bounds.x,
             bounds.y,
             WHITE,
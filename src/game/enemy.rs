@@ -0,0 +1,490 @@
+use macroquad::prelude::*;
+use std::time::Duration;
+
+use crate::game::spawn;
+use crate::game::Map;
+use crate::game::Route;
+use crate::game::ScentTrail;
+use crate::game::Settings;
+
+use super::player::Direction;
+
+#[derive(Debug, Clone)]
+pub struct Enemy {
+    settings: Settings,
+    texture: Texture2D,
+    frame_counter: u32,
+    frame_delay: Duration,
+    last_frame_update: std::time::Instant,
+    state: State,
+    timer: f32,
+    knockback: Vec2,
+    scent: u64,
+    bite_cooldown: f32,
+    route: Route,
+    // Time left before a failed or finished route is tried again
+    repath_cooldown: f32,
+    pub kind: EnemyKind,
+    // Where it was placed, its room state keeps it dead
+    pub id: String,
+    pub home_room: String,
+    pub room: String,
+    pub direction: Direction,
+    pub position: Vec2,
+    pub home: Vec2,
+    pub target: Vec2,
+    pub speed: f32,
+    pub bounds: Rect,
+    pub collider: Rect,
+    pub health: f32,
+}
+
+// Enums
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EnemyKind {
+    Guard,
+    Dog,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    Patrolling,
+    Investigating,
+    Searching,
+    Returning,
+    Tracking,
+    Stunned,
+    Dead,
+}
+
+// Frame Size
+const FS_GUARD: Vec2 = Vec2::new(15.0, 32.0);
+const FS_DOG: Vec2 = Vec2::new(31.0, 18.0);
+const FS_DOG_FRONT: Vec2 = Vec2::new(15.0, 27.0);
+const FS_DOG_BACK: Vec2 = Vec2::new(10.0, 27.0);
+
+// Sheet layout: three frames each for Down, Left, Up, Right
+const SHEET_X: [f32; 12] = [
+    1.0, 17.0, 33.0, 49.0, 66.0, 83.0, 100.0, 116.0, 132.0, 148.0, 165.0, 182.0,
+];
+const SHEET_Y: f32 = 1.0;
+
+// Max Frames
+const MF_WALKING: u32 = 3;
+const MF_DOG: u32 = 2;
+
+// Conts
+const SCALE: f32 = 4.0;
+const SPEED: f32 = 120.0;
+const DOG_SPEED: f32 = 260.0;
+const SHUTTER: u64 = 224;
+const HEALTH: f32 = 3.0;
+const TURN: f32 = 3.0;
+const SEARCH: f32 = 2.0;
+const STUN: f32 = 1.5;
+const FRICTION: f32 = 8.0;
+const ARRIVED: f32 = 4.0;
+const SNIFF_RANGE: f32 = 256.0;
+const CRUMB_REACH: f32 = 24.0;
+const BITE_COOLDOWN: f32 = 1.0;
+const REPATH: f32 = 32.0;
+const REPATH_COOLDOWN: f32 = 1.0;
+
+impl Enemy {
+    // Public
+    pub async fn new(settings: Settings, kind: EnemyKind, position: Vec2, room: &str) -> Self {
+        // Load Textures
+        let texture_path = match kind {
+            EnemyKind::Guard => "assets/enemies/enemies.png",
+            EnemyKind::Dog => "assets/enemies/dog_walking.png",
+        };
+        let texture = load_texture(texture_path).await.unwrap();
+        texture.set_filter(FilterMode::Nearest);
+
+        let (frame_size, speed) = match kind {
+            EnemyKind::Guard => (FS_GUARD, SPEED),
+            EnemyKind::Dog => (FS_DOG, DOG_SPEED),
+        };
+        let size = frame_size * SCALE * 0.8;
+        let collider = Rect::new(position.x, position.y + size.y * 0.5, size.x, size.y * 0.5);
+
+        Self {
+            settings,
+            texture,
+            frame_counter: 0,
+            frame_delay: Duration::from_millis(SHUTTER),
+            last_frame_update: std::time::Instant::now(),
+            state: State::Patrolling,
+            timer: 0.0,
+            knockback: Vec2::ZERO,
+            scent: 0,
+            bite_cooldown: 0.0,
+            route: Route::default(),
+            repath_cooldown: 0.0,
+            kind,
+            id: spawn::id(room, position),
+            home_room: room.to_string(),
+            room: room.to_string(),
+            direction: Direction::Down,
+            position,
+            home: collider.center(),
+            target: collider.center(),
+            speed,
+            bounds: Rect::new(position.x, position.y, size.x, size.y),
+            collider,
+            health: HEALTH,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32, map: &Map) {
+        self.settings.update();
+
+        let colliders: &[Rect] = match map.find_room(&self.room) {
+            Some(room) => &room.collider_map.colliders,
+            None => &[],
+        };
+
+        self.bite_cooldown = (self.bite_cooldown - delta_time).max(0.0);
+        self.repath_cooldown = (self.repath_cooldown - delta_time).max(0.0);
+
+        match self.state {
+            State::Patrolling => {
+                self.timer += delta_time;
+                if self.timer >= TURN {
+                    self.timer = 0.0;
+                    self.direction = self.direction.clockwise();
+                }
+            }
+
+            State::Investigating => {
+                if self.follow_route(self.target, delta_time, colliders) {
+                    self.state = State::Searching;
+                    self.timer = 0.0;
+                }
+            }
+
+            State::Searching => {
+                // Look around the spot a few times before giving up
+                let before = (self.timer / (SEARCH / 4.0)) as u32;
+                self.timer += delta_time;
+                let after = (self.timer / (SEARCH / 4.0)) as u32;
+                if after > before {
+                    self.direction = self.direction.clockwise();
+                }
+
+                if self.timer >= SEARCH {
+                    self.route = self.find_route(self.home, map);
+                    self.state = State::Returning;
+                    self.timer = 0.0;
+                }
+            }
+
+            State::Returning => {
+                if self.follow_route(self.home, delta_time, colliders) {
+                    self.state = State::Patrolling;
+                    self.timer = 0.0;
+                }
+            }
+
+            State::Tracking => {
+                self.walk_to(self.target, delta_time, colliders);
+            }
+
+            State::Stunned => {
+                self.timer += delta_time;
+
+                let step = self.knockback * delta_time;
+                if !self.try_move(Vec2::new(step.x, 0.0), colliders) {
+                    self.knockback.x = 0.0;
+                }
+                if !self.try_move(Vec2::new(0.0, step.y), colliders) {
+                    self.knockback.y = 0.0;
+                }
+                self.knockback *= (1.0 - FRICTION * delta_time).max(0.0);
+
+                if self.timer >= STUN {
+                    self.knockback = Vec2::ZERO;
+                    self.state = State::Searching;
+                    self.timer = 0.0;
+                }
+            }
+
+            State::Dead => {}
+        }
+    }
+
+    pub fn draw(&self) {
+        if self.state == State::Dead {
+            return;
+        }
+
+        let src_rect = self.source_rect();
+
+        // Set dest, frames differ in size so stand them on the bounds
+        let size = src_rect.size() * SCALE * 0.8;
+        let dest = Rect::new(
+            self.bounds.center().x - size.x / 2.0,
+            self.bounds.bottom() - size.y,
+            size.x,
+            size.y,
+        );
+
+        // Draw
+        if self.settings.debug {
+            draw_rectangle(
+                self.collider.x,
+                self.collider.y,
+                self.collider.w,
+                self.collider.h,
+                Color::new(1.0, 0.5, 0.0, 0.5),
+            );
+
+            if !self.route.is_empty() {
+                self.route.draw(self.collider.center(), ORANGE);
+            } else if self.state == State::Investigating || self.state == State::Tracking {
+                draw_line(
+                    self.collider.center().x,
+                    self.collider.center().y,
+                    self.target.x,
+                    self.target.y,
+                    2.0,
+                    ORANGE,
+                );
+            }
+        }
+
+        let tint = if self.state == State::Stunned {
+            GRAY
+        } else {
+            WHITE
+        };
+
+        draw_texture_ex(
+            &self.texture,
+            dest.x,
+            dest.y,
+            tint,
+            DrawTextureParams {
+                source: Some(src_rect),
+                dest_size: Some(dest.size()),
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn hit(&mut self, damage: f32, push: Vec2) {
+        if self.state == State::Dead {
+            return;
+        }
+
+        self.health -= damage;
+        self.route.clear();
+        if self.health <= 0.0 {
+            self.state = State::Dead;
+        } else {
+            self.state = State::Stunned;
+            self.knockback = push;
+            self.timer = 0.0;
+        }
+    }
+
+    pub fn investigate(&mut self, point: Vec2, map: &Map) {
+        if self.state == State::Stunned || self.state == State::Dead {
+            return;
+        }
+
+        // Keep to the current route unless the spot has really moved. With
+        // no route left, because it failed or ran out, wait a while before
+        // searching again for the same spot.
+        let same_spot = self.state == State::Investigating && self.target.distance(point) < REPATH;
+        if same_spot && (!self.route.is_empty() || self.repath_cooldown > 0.0) {
+            return;
+        }
+
+        self.route = self.find_route(point, map);
+        self.repath_cooldown = REPATH_COOLDOWN;
+        self.target = point;
+        self.state = State::Investigating;
+        self.timer = 0.0;
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.state != State::Dead
+    }
+
+    pub fn is_awake(&self) -> bool {
+        self.state != State::Stunned && self.state != State::Dead
+    }
+
+    pub fn is_tracking(&self) -> bool {
+        self.state == State::Tracking
+    }
+
+    pub fn has_route(&self) -> bool {
+        !self.route.is_empty()
+    }
+
+    // Dogs pick up the trail nearby and follow it crumb by crumb
+    pub fn sniff(&mut self, trail: &ScentTrail) {
+        if self.kind != EnemyKind::Dog {
+            return;
+        }
+
+        let nose = self.collider.center();
+        match self.state {
+            State::Patrolling | State::Searching | State::Returning | State::Investigating => {
+                if let Some(crumb) = trail.sniff(nose, SNIFF_RANGE) {
+                    self.scent = crumb.id;
+                    self.target = crumb.position;
+                    self.state = State::Tracking;
+                    self.timer = 0.0;
+                }
+            }
+
+            State::Tracking => {
+                if !trail.still_fresh(self.scent) {
+                    // Lost it
+                    self.state = State::Searching;
+                    self.timer = 0.0;
+                    return;
+                }
+
+                if nose.distance(self.target) <= CRUMB_REACH {
+                    if let Some(crumb) = trail.after(self.scent) {
+                        self.scent = crumb.id;
+                        self.target = crumb.position;
+                    }
+                }
+            }
+
+            State::Stunned | State::Dead => {}
+        }
+    }
+
+    pub fn try_bite(&mut self) -> bool {
+        if self.kind != EnemyKind::Dog || !self.is_tracking() || self.bite_cooldown > 0.0 {
+            return false;
+        }
+
+        self.bite_cooldown = BITE_COOLDOWN;
+        true
+    }
+
+    // Private
+    fn find_route(&self, target: Vec2, map: &Map) -> Route {
+        // No route, walk straight at it and slide along what's in the way
+        Route::find(map, self.collider.center(), target, self.collider.size()).unwrap_or_default()
+    }
+
+    fn follow_route(&mut self, target: Vec2, delta_time: f32, colliders: &[Rect]) -> bool {
+        match self.route.next() {
+            Some(point) => {
+                if self.walk_to(point, delta_time, colliders) {
+                    self.route.advance();
+                }
+                false
+            }
+            None => self.walk_to(target, delta_time, colliders),
+        }
+    }
+
+    fn walk_to(&mut self, target: Vec2, delta_time: f32, colliders: &[Rect]) -> bool {
+        // Head for the target with the feet, same as the collider
+        let to = target - self.collider.center();
+        if to.length() <= ARRIVED {
+            return true;
+        }
+
+        self.update_frame_counter();
+
+        // Walk one axis at a time, the longer first
+        let step = self.speed * delta_time;
+        let along_x = Vec2::new(to.x.signum() * step.min(to.x.abs()), 0.0);
+        let along_y = Vec2::new(0.0, to.y.signum() * step.min(to.y.abs()));
+        let (first, second) = if to.x.abs() > to.y.abs() {
+            (along_x, along_y)
+        } else {
+            (along_y, along_x)
+        };
+
+        if first.length() > 0.0 && self.try_move(first, colliders) {
+            self.direction = Direction::from_vector(first);
+            return false;
+        }
+        if second.length() > 0.0 && self.try_move(second, colliders) {
+            self.direction = Direction::from_vector(second);
+            return false;
+        }
+
+        // Blocked both ways, close enough
+        true
+    }
+
+    fn try_move(&mut self, delta: Vec2, colliders: &[Rect]) -> bool {
+        let moved = self.collider.offset(delta);
+
+        // Inset so sliding along a wall doesn't count as touching it
+        let probe = Rect::new(moved.x + 0.5, moved.y + 0.5, moved.w - 1.0, moved.h - 1.0);
+        if colliders.iter().any(|collider| collider.overlaps(&probe)) {
+            return false;
+        }
+
+        self.position += delta;
+        self.bounds = self.bounds.offset(delta);
+        self.collider = moved;
+        true
+    }
+
+    fn source_rect(&self) -> Rect {
+        match self.kind {
+            EnemyKind::Guard => {
+                let column = match self.direction {
+                    Direction::Down => 0,
+                    Direction::Left => 3,
+                    Direction::Up => 6,
+                    Direction::Right => 9,
+                };
+                let frame = match self.state {
+                    State::Investigating | State::Returning | State::Tracking => {
+                        self.frame_counter % MF_WALKING
+                    }
+                    _ => 0,
+                };
+                Rect::new(
+                    SHEET_X[column + frame as usize],
+                    SHEET_Y,
+                    FS_GUARD.x,
+                    FS_GUARD.y,
+                )
+            }
+            EnemyKind::Dog => {
+                let frame = (self.frame_counter % MF_DOG) as f32;
+                match self.direction {
+                    Direction::Left => Rect::new(1.0 + 33.0 * frame, 1.0, FS_DOG.x, FS_DOG.y),
+                    Direction::Right => Rect::new(1.0 + 33.0 * frame, 20.0, FS_DOG.x, FS_DOG.y),
+                    Direction::Down => Rect::new(1.0, 39.0, FS_DOG_FRONT.x, FS_DOG_FRONT.y),
+                    Direction::Up => {
+                        Rect::new(17.0 + 11.0 * frame, 39.0, FS_DOG_BACK.x, FS_DOG_BACK.y)
+                    }
+                }
+            }
+        }
+    }
+
+    fn update_frame_counter(&mut self) {
+        // Update time vars
+        let now = std::time::Instant::now();
+        let elapsed = now - self.last_frame_update;
+
+        // Check frame vs time
+        if elapsed >= self.frame_delay {
+            self.last_frame_update = now;
+            let frames = elapsed.as_secs_f32() / self.frame_delay.as_secs_f32();
+            let max_frames = match self.kind {
+                EnemyKind::Guard => MF_WALKING,
+                EnemyKind::Dog => MF_DOG,
+            };
+            self.frame_counter = (self.frame_counter + (frames as u32)) % max_frames;
+        }
+    }
+}
@@ -1,44 +1,69 @@
 use macroquad::prelude::*;
 use std::path::Path;
+use std::time::Duration;
+
+use crate::game::Inventory;
+use crate::game::{Anchor, Ui};
+use crate::game::{NoiseKind, Projectile};
+
+use super::itemeffect::{self, ItemEffect};
 
 #[derive(Debug, Clone)]
 pub struct EquipMenu {
     pub pause: bool,
+    // Holding Q or E flicks through what's owned, Tab opens the full screen
+    pub quick_swap: bool,
+    screen: bool,
+    cursor: usize,
     side: bool,
     pub left_selected: usize,
-    left_selected_up: usize,
-    left_selected_down: usize,
     pub right_selected: usize,
-    right_selected_up: usize,
-    right_selected_down: usize,
+    // What was in hand when the full screen opened, for backing out
+    opened_with: (usize, usize),
     bg_texture: Texture2D,
     item_textures: Vec<Texture2D>,
     weapon_textures: Vec<Texture2D>,
+    pub inventory: Inventory,
 }
 
 const WIDTH: f32 = 128.0;
 const HEIGHT: f32 = 96.0;
-const LEFT_X_OS: f32 = 628.0;
-const RIGHT_X_0S: f32 = LEFT_X_OS - WIDTH;
-const Y_OS: f32 = 256.0;
+const PANEL_SIZE: Vec2 = Vec2::new(WIDTH, HEIGHT);
+// Panels sit in the bottom corners, in from the edges by this much
+const PANEL_OFFSET: Vec2 = Vec2::new(12.0, 8.0);
 const ITEM_WIDTH: f32 = 140.0;
 const ITEM_HEIGHT: f32 = 68.0;
 const ITEM_X_OFFSET: f32 = WIDTH / 2.0 - ITEM_WIDTH / 2.0;
 const ITEM_Y_OFFSET: f32 = HEIGHT / 2.0 - ITEM_HEIGHT / 2.0;
 const TEXT_X_OFFSET: f32 = WIDTH / 4.0;
 const TEXT_Y_OFFSET: f32 = HEIGHT;
+const COUNT_Y_OFFSET: f32 = 20.0;
+
+// Icons cut from a sheet get centred on a canvas the size of the icon files
+const ICON_CANVAS: Vec2 = Vec2::new(35.0, 17.0);
+const SHEET_CELL: f32 = 16.0;
 
-const OFFSET_NEXT_X: f32 = 160.0;
-const OFFSET_NEXT_Y: f32 = -128.0;
+// Neighbours in the open menu, further in from the corner
+const NEXT_ACROSS: Vec2 = Vec2::new(160.0, 0.0);
+const NEXT_UP: Vec2 = Vec2::new(0.0, 128.0);
 
-const ITEM_SIZE: usize = 2 - 1;
-const WEAPON_SIZE: usize = 2 - 1;
+// Full screen grid
+const COLUMNS: usize = 4;
+const CELL_GAP: f32 = 16.0;
+const GRID_TOP: f32 = 112.0;
+const SHADE: Color = Color::new(0.0, 0.0, 0.0, 0.8);
+const UNOWNED: Color = Color::new(0.25, 0.25, 0.25, 1.0);
+
+const ITEM_SIZE: usize = Item::ALL.len() - 1;
+const WEAPON_SIZE: usize = Weapon::ALL.len() - 1;
 
 impl EquipMenu {
     pub async fn new() -> Self {
         let bg_texture = load_texture("assets/items/background.png").await.unwrap();
         bg_texture.set_filter(FilterMode::Nearest);
 
+        let item_sheet = load_image("assets/items/items.png").await.unwrap();
+
         let mut item_textures = Vec::new();
         let mut weapon_textures = Vec::new();
 
@@ -51,11 +76,14 @@ impl EquipMenu {
                 texture.set_filter(FilterMode::Nearest);
 
                 item_textures.push(texture);
+            } else if let Some(cell) = Item::from_index(id).and_then(|item| item.sheet_cell()) {
+                item_textures.push(EquipMenu::sheet_icon(&item_sheet, cell));
             }
         }
 
-        for id in 0..=WEAPON_SIZE {
-            let path = format!("assets/weapons/{:02}.png", id);
+        let weapon_sheet = load_image("assets/weapons/weapons.png").await.unwrap();
+        for weapon in Weapon::ALL {
+            let path = format!("assets/weapons/{:02}.png", weapon as usize);
 
             let p = Path::new(&path);
             if p.exists() {
@@ -63,25 +91,49 @@ impl EquipMenu {
                 texture.set_filter(FilterMode::Nearest);
 
                 weapon_textures.push(texture);
+            } else if let Some(cell) = weapon.sheet_cell() {
+                weapon_textures.push(EquipMenu::weapon_sheet_icon(&weapon_sheet, cell));
+            } else if let Some(sprite) = weapon.sprite() {
+                let sprite = load_image(sprite).await.unwrap();
+                weapon_textures.push(EquipMenu::centred_icon(&sprite));
+            } else {
+                // Name only
+                weapon_textures.push(EquipMenu::centred_icon(&Image::empty()));
             }
         }
 
         Self {
             pause: false,
+            quick_swap: true,
+            screen: false,
+            cursor: 0,
             side: false,
             left_selected: Item::Cigs as usize,
-            left_selected_up: Item::Empty as usize,
-            left_selected_down: Item::Empty as usize,
-            right_selected: Weapon::Handgun as usize,
-            right_selected_up: Weapon::Empty as usize,
-            right_selected_down: Weapon::Empty as usize,
+            right_selected: Weapon::Empty as usize,
+            opened_with: (Item::Cigs as usize, Weapon::Empty as usize),
             bg_texture,
             item_textures,
             weapon_textures,
+            inventory: Inventory::new(),
         }
     }
 
     pub fn update(&mut self) {
+        if self.screen {
+            self.update_screen();
+            return;
+        }
+
+        if is_key_pressed(KeyCode::Tab) {
+            self.open_screen();
+            return;
+        }
+
+        if !self.quick_swap {
+            self.pause = false;
+            return;
+        }
+
         if is_key_down(KeyCode::Q) {
             self.pause = true;
             self.side = false;
@@ -95,40 +147,185 @@ impl EquipMenu {
         if self.pause {
             if !self.side {
                 if is_key_pressed(KeyCode::Up) {
-                    self.left_selected =
-                        self.update_index(self.left_selected as f32, 1.0, ITEM_SIZE);
-                    self.left_selected_up =
-                        self.update_index(self.left_selected_up as f32, 1.0, ITEM_SIZE);
-                    self.left_selected_down =
-                        self.update_index(self.left_selected_down as f32, 1.0, ITEM_SIZE);
+                    self.left_selected = self.next_item(self.left_selected, 1.0);
                 } else if is_key_pressed(KeyCode::Down) {
-                    self.left_selected =
-                        self.update_index(self.left_selected as f32, -1.0, ITEM_SIZE);
-                    self.left_selected_up =
-                        self.update_index(self.left_selected_up as f32, -1.0, ITEM_SIZE);
-                    self.left_selected_down =
-                        self.update_index(self.left_selected_down as f32, -1.0, ITEM_SIZE);
+                    self.left_selected = self.next_item(self.left_selected, -1.0);
                 }
             } else {
                 if is_key_pressed(KeyCode::Up) {
-                    self.right_selected =
-                        self.update_index(self.right_selected as f32, 1.0, WEAPON_SIZE);
-                    self.right_selected_up =
-                        self.update_index(self.right_selected_up as f32, 1.0, WEAPON_SIZE);
-                    self.right_selected_down =
-                        self.update_index(self.right_selected_down as f32, 1.0, WEAPON_SIZE);
+                    self.right_selected = self.next_weapon(self.right_selected, 1.0);
                 } else if is_key_pressed(KeyCode::Down) {
-                    self.right_selected =
-                        self.update_index(self.right_selected as f32, -1.0, WEAPON_SIZE);
-                    self.right_selected_up =
-                        self.update_index(self.right_selected_up as f32, -1.0, WEAPON_SIZE);
-                    self.right_selected_down =
-                        self.update_index(self.right_selected_down as f32, -1.0, WEAPON_SIZE);
+                    self.right_selected = self.next_weapon(self.right_selected, -1.0);
+                }
+            }
+        }
+    }
+
+    pub fn equip_item(&mut self, item: Item) {
+        if self.inventory.owns_item(item) {
+            self.left_selected = item as usize;
+        }
+    }
+
+    pub fn equip_weapon(&mut self, weapon: Weapon) {
+        if self.inventory.owns_weapon(weapon) {
+            self.right_selected = weapon as usize;
+        }
+    }
+
+    pub fn item_icon(&self, item: Item) -> Texture2D {
+        self.item_textures[item as usize].clone()
+    }
+
+    pub fn weapon_icon(&self, weapon: Weapon) -> Texture2D {
+        self.weapon_textures[weapon as usize].clone()
+    }
+
+    // Step through the lists, skipping anything Snake doesn't have
+    fn next_item(&self, index: usize, direction: f32) -> usize {
+        let mut next = index;
+        for _ in 0..=ITEM_SIZE {
+            next = self.update_index(next as f32, direction, ITEM_SIZE);
+            if Item::from_index(next).is_some_and(|item| self.inventory.owns_item(item)) {
+                return next;
+            }
+        }
+        index
+    }
+
+    fn next_weapon(&self, index: usize, direction: f32) -> usize {
+        let mut next = index;
+        for _ in 0..=WEAPON_SIZE {
+            next = self.update_index(next as f32, direction, WEAPON_SIZE);
+            if Weapon::from_index(next).is_some_and(|weapon| self.inventory.owns_weapon(weapon)) {
+                return next;
+            }
+        }
+        index
+    }
+
+    // Full screen
+
+    fn open_screen(&mut self) {
+        self.screen = true;
+        self.pause = true;
+        self.opened_with = (self.left_selected, self.right_selected);
+
+        // Start on whatever is in hand
+        let selected = if self.side {
+            self.right_selected
+        } else {
+            self.left_selected
+        };
+        self.cursor = selected.saturating_sub(1);
+    }
+
+    fn update_screen(&mut self) {
+        // Tab keeps what's been picked, Backspace puts back what was in hand
+        if is_key_pressed(KeyCode::Backspace) {
+            (self.left_selected, self.right_selected) = self.opened_with;
+        }
+        if is_key_pressed(KeyCode::Tab) || is_key_pressed(KeyCode::Backspace) {
+            self.screen = false;
+            self.pause = false;
+            return;
+        }
+
+        if is_key_pressed(KeyCode::Q) && self.side {
+            self.side = false;
+            self.cursor = self.left_selected.saturating_sub(1);
+        } else if is_key_pressed(KeyCode::E) && !self.side {
+            self.side = true;
+            self.cursor = self.right_selected.saturating_sub(1);
+        }
+
+        if is_key_pressed(KeyCode::R) {
+            self.quick_swap = !self.quick_swap;
+        }
+
+        // Empty isn't on the grid, unequipping takes its place
+        let slots = if self.side { WEAPON_SIZE } else { ITEM_SIZE };
+        if is_key_pressed(KeyCode::Right) {
+            self.cursor = (self.cursor + 1).min(slots - 1);
+        } else if is_key_pressed(KeyCode::Left) {
+            self.cursor = self.cursor.saturating_sub(1);
+        } else if is_key_pressed(KeyCode::Down) && self.cursor + COLUMNS < slots {
+            self.cursor += COLUMNS;
+        } else if is_key_pressed(KeyCode::Up) && self.cursor >= COLUMNS {
+            self.cursor -= COLUMNS;
+        }
+
+        // Enter equips, or puts back what's already in hand
+        if is_key_pressed(KeyCode::Enter) {
+            let index = self.cursor + 1;
+            if self.side {
+                let weapon = Weapon::from_index(index).unwrap();
+                if self.right_selected == index {
+                    self.equip_weapon(Weapon::Empty);
+                } else {
+                    self.equip_weapon(weapon);
+                }
+            } else {
+                let item = Item::from_index(index).unwrap();
+                if self.left_selected == index {
+                    self.equip_item(Item::Empty);
+                } else {
+                    self.equip_item(item);
                 }
             }
         }
     }
 
+    fn sheet_icon(sheet: &Image, cell: (u32, u32)) -> Texture2D {
+        let mut icon = Image::gen_image_color(ICON_CANVAS.x as u16, ICON_CANVAS.y as u16, BLANK);
+
+        // Sheet cells are 16px with a 1px border
+        let origin_x = 1 + cell.0 * (SHEET_CELL as u32 + 1);
+        let origin_y = 1 + cell.1 * (SHEET_CELL as u32 + 1);
+        let offset_x = ((ICON_CANVAS.x - SHEET_CELL) / 2.0) as u32;
+        for y in 0..SHEET_CELL as u32 {
+            for x in 0..SHEET_CELL as u32 {
+                let pixel = sheet.get_pixel(origin_x + x, origin_y + y);
+                icon.set_pixel(offset_x + x, y, pixel);
+            }
+        }
+
+        let texture = Texture2D::from_image(&icon);
+        texture.set_filter(FilterMode::Nearest);
+        texture
+    }
+
+    // Weapon sheet cells are already icon sized
+    fn weapon_sheet_icon(sheet: &Image, cell: (u32, u32)) -> Texture2D {
+        let origin = Vec2::new(
+            1.0 + cell.0 as f32 * (ICON_CANVAS.x + 1.0),
+            1.0 + cell.1 as f32 * (ICON_CANVAS.y + 1.0),
+        );
+        let icon = sheet.sub_image(Rect::new(origin.x, origin.y, ICON_CANVAS.x, ICON_CANVAS.y));
+
+        let texture = Texture2D::from_image(&icon);
+        texture.set_filter(FilterMode::Nearest);
+        texture
+    }
+
+    fn centred_icon(sprite: &Image) -> Texture2D {
+        let mut icon = Image::gen_image_color(ICON_CANVAS.x as u16, ICON_CANVAS.y as u16, BLANK);
+
+        let width = (sprite.width() as u32).min(ICON_CANVAS.x as u32);
+        let height = (sprite.height() as u32).min(ICON_CANVAS.y as u32);
+        let offset_x = (ICON_CANVAS.x as u32 - width) / 2;
+        let offset_y = (ICON_CANVAS.y as u32 - height) / 2;
+        for y in 0..height {
+            for x in 0..width {
+                icon.set_pixel(offset_x + x, offset_y + y, sprite.get_pixel(x, y));
+            }
+        }
+
+        let texture = Texture2D::from_image(&icon);
+        texture.set_filter(FilterMode::Nearest);
+        texture
+    }
+
     fn update_index(&self, mut index: f32, direction: f32, limit: usize) -> usize {
         index += direction;
         if index > (limit as f32) {
@@ -136,116 +333,258 @@ impl EquipMenu {
         } else if index < 0.0 {
             index = limit as f32;
         }
-        return index as usize;
+        index as usize
     }
 
-    pub fn draw(&self, camera_position: Vec2) {
-        if self.pause {
+    pub fn draw(&self, ui: &Ui) {
+        if self.screen {
+            self.draw_screen(ui);
+        } else if self.pause {
             if !self.side {
-                self.draw_left_item(
-                    self.left_selected_up,
-                    camera_position,
-                    Vec2::new(0.0, OFFSET_NEXT_Y),
-                );
-                self.draw_left_item(self.left_selected, camera_position, Vec2::new(0.0, 0.0));
-                self.draw_left_item(
-                    self.left_selected_down,
-                    camera_position,
-                    Vec2::new(OFFSET_NEXT_X, 0.0),
+                let up = ui.rect(Anchor::BottomLeft, PANEL_OFFSET + NEXT_UP, PANEL_SIZE);
+                ui.text(
+                    &format!("Rank {}", "*".repeat(self.inventory.rank() as usize + 1)),
+                    vec2(up.x, up.y - ui.px(COUNT_Y_OFFSET)),
+                    24.0,
+                    WHITE,
                 );
+                let up = self.next_item(self.left_selected, 1.0);
+                let down = self.next_item(self.left_selected, -1.0);
+                self.draw_left_item(up, ui, NEXT_UP);
+                self.draw_left_item(self.left_selected, ui, Vec2::ZERO);
+                self.draw_left_item(down, ui, NEXT_ACROSS);
             } else {
-                self.draw_right_weapon(
-                    self.right_selected_up,
-                    camera_position,
-                    Vec2::new(0.0, OFFSET_NEXT_Y),
-                );
-                self.draw_right_weapon(self.right_selected, camera_position, Vec2::new(0.0, 0.0));
-                self.draw_right_weapon(
-                    self.right_selected_down,
-                    camera_position,
-                    Vec2::new(-OFFSET_NEXT_X, 0.0),
-                );
-            }
-        } else {
-            if self.left_selected != (Item::Empty as usize) {
-                self.draw_left_item(self.left_selected, camera_position, Vec2::new(0.0, 0.0));
-            }
-
-            if self.right_selected != (Weapon::Empty as usize) {
-                self.draw_right_weapon(self.right_selected, camera_position, Vec2::new(0.0, 0.0));
+                let up = self.next_weapon(self.right_selected, 1.0);
+                let down = self.next_weapon(self.right_selected, -1.0);
+                self.draw_right_weapon(up, ui, NEXT_UP);
+                self.draw_right_weapon(self.right_selected, ui, Vec2::ZERO);
+                self.draw_right_weapon(down, ui, NEXT_ACROSS);
             }
         }
     }
 
-    fn draw_left_item(&self, index: usize, camera_position: Vec2, offset_position: Vec2) {
-        draw_texture_ex(
-            &self.bg_texture,
-            camera_position.x - LEFT_X_OS + offset_position.x,
-            camera_position.y + Y_OS + offset_position.y,
+    fn draw_left_item(&self, index: usize, ui: &Ui, offset: Vec2) {
+        let item = Item::from_index(index).unwrap();
+        let panel = ui.rect(Anchor::BottomLeft, PANEL_OFFSET + offset, PANEL_SIZE);
+
+        self.draw_panel(
+            ui,
+            panel,
+            &self.item_textures[index],
+            &item.to_string(),
+            self.count_text(item),
             WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(WIDTH, HEIGHT)),
-                ..Default::default()
-            },
         );
+    }
 
-        draw_texture_ex(
-            &self.item_textures[index],
-            camera_position.x - LEFT_X_OS + ITEM_X_OFFSET + offset_position.x,
-            camera_position.y + Y_OS + ITEM_Y_OFFSET + offset_position.y,
+    fn draw_right_weapon(&self, index: usize, ui: &Ui, offset: Vec2) {
+        let weapon = Weapon::from_index(index).unwrap();
+        let panel = ui.rect(Anchor::BottomRight, PANEL_OFFSET + offset, PANEL_SIZE);
+
+        self.draw_panel(
+            ui,
+            panel,
+            &self.weapon_textures[index],
+            &weapon.to_string(),
+            self.ammo_text(weapon),
             WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(ITEM_WIDTH, ITEM_HEIGHT)),
-                ..Default::default()
-            },
         );
+    }
+
+    // Everything there is on a grid, what Snake hasn't found yet greyed out
+    fn draw_screen(&self, ui: &Ui) {
+        let screen = ui.size();
+        draw_rectangle(0.0, 0.0, ui.px(screen.x), ui.px(screen.y), SHADE);
+
+        let slots = if self.side { WEAPON_SIZE } else { ITEM_SIZE };
+        let rows = slots.div_ceil(COLUMNS);
+        let step = PANEL_SIZE + Vec2::splat(CELL_GAP);
+        let grid_size = vec2(
+            step.x * COLUMNS as f32 - CELL_GAP,
+            step.y * rows as f32 - CELL_GAP,
+        );
+        let grid = ui.rect(Anchor::Top, vec2(0.0, GRID_TOP), grid_size);
+
+        // Tabs, the open one lit
+        let tab_y = grid.y - ui.px(CELL_GAP * 2.0);
+        let (items_color, weapons_color) = if self.side {
+            (GRAY, YELLOW)
+        } else {
+            (YELLOW, GRAY)
+        };
+        ui.text("ITEMS (Q)", vec2(grid.x, tab_y), 32.0, items_color);
+        ui.text(
+            "WEAPONS (E)",
+            vec2(grid.x + grid.w / 2.0, tab_y),
+            32.0,
+            weapons_color,
+        );
+
+        for slot in 0..slots {
+            let index = slot + 1;
+            let cell = vec2((slot % COLUMNS) as f32, (slot / COLUMNS) as f32) * step;
+            let panel = Rect::new(
+                grid.x + ui.px(cell.x),
+                grid.y + ui.px(cell.y),
+                ui.px(PANEL_SIZE.x),
+                ui.px(PANEL_SIZE.y),
+            );
 
-        draw_text(
-            &Item::from_index(index).unwrap().to_string(),
-            camera_position.x - LEFT_X_OS + TEXT_X_OFFSET + offset_position.x,
-            camera_position.y + Y_OS + TEXT_Y_OFFSET + offset_position.y,
+            let (icon, name, count, owned, equipped) = if self.side {
+                let weapon = Weapon::from_index(index).unwrap();
+                (
+                    &self.weapon_textures[index],
+                    weapon.to_string(),
+                    self.ammo_text(weapon),
+                    self.inventory.owns_weapon(weapon),
+                    self.right_selected == index,
+                )
+            } else {
+                let item = Item::from_index(index).unwrap();
+                (
+                    &self.item_textures[index],
+                    item.to_string(),
+                    self.count_text(item),
+                    self.inventory.owns_item(item),
+                    self.left_selected == index,
+                )
+            };
+
+            if owned {
+                self.draw_panel(ui, panel, icon, &name, count, WHITE);
+            } else {
+                self.draw_panel(ui, panel, icon, "", None, UNOWNED);
+            }
+
+            if equipped {
+                draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, ui.px(2.0), GREEN);
+            }
+            if slot == self.cursor {
+                draw_rectangle_lines(panel.x, panel.y, panel.w, panel.h, ui.px(4.0), YELLOW);
+            }
+        }
+
+        // What's under the cursor
+        let index = self.cursor + 1;
+        let (name, description, owned) = if self.side {
+            let weapon = Weapon::from_index(index).unwrap();
+            (
+                weapon.to_string(),
+                weapon.description(),
+                self.inventory.owns_weapon(weapon),
+            )
+        } else {
+            let item = Item::from_index(index).unwrap();
+            (
+                item.to_string(),
+                item.description(),
+                self.inventory.owns_item(item),
+            )
+        };
+        let (name, description) = if owned {
+            (name, description)
+        } else {
+            ("???".to_string(), "Not found yet.")
+        };
+
+        let text_y = grid.bottom() + ui.px(CELL_GAP * 3.0);
+        ui.text(&name, vec2(grid.x, text_y), 32.0, WHITE);
+        ui.text(description, vec2(grid.x, text_y + ui.px(32.0)), 24.0, WHITE);
+
+        let quick_swap = if self.quick_swap { "on" } else { "off" };
+        ui.text(
+            &format!(
+                "Enter equip   Backspace back   R quick swap: {}",
+                quick_swap
+            ),
+            vec2(grid.x, text_y + ui.px(72.0)),
             24.0,
-            WHITE,
+            GRAY,
         );
     }
 
-    fn draw_right_weapon(&self, index: usize, camera_position: Vec2, offset_position: Vec2) {
+    fn count_text(&self, item: Item) -> Option<(String, Color)> {
+        self.inventory
+            .count(item)
+            .map(|(count, _)| (count.to_string(), WHITE))
+    }
+
+    // Red once it's run dry
+    fn ammo_text(&self, weapon: Weapon) -> Option<(String, Color)> {
+        self.inventory.ammo(weapon).map(|(ammo, capacity)| {
+            let color = if ammo == 0 { RED } else { WHITE };
+            (format!("{}/{}", ammo, capacity), color)
+        })
+    }
+
+    fn draw_panel(
+        &self,
+        ui: &Ui,
+        panel: Rect,
+        icon: &Texture2D,
+        name: &str,
+        count: Option<(String, Color)>,
+        tint: Color,
+    ) {
         draw_texture_ex(
             &self.bg_texture,
-            camera_position.x + RIGHT_X_0S + offset_position.x,
-            camera_position.y + Y_OS + offset_position.y,
-            WHITE,
+            panel.x,
+            panel.y,
+            tint,
             DrawTextureParams {
-                dest_size: Some(vec2(WIDTH, HEIGHT)),
+                dest_size: Some(panel.size()),
                 ..Default::default()
             },
         );
 
         draw_texture_ex(
-            &self.weapon_textures[index],
-            camera_position.x + RIGHT_X_0S + ITEM_X_OFFSET + offset_position.x,
-            camera_position.y + Y_OS + ITEM_Y_OFFSET + offset_position.y,
-            WHITE,
+            icon,
+            panel.x + ui.px(ITEM_X_OFFSET),
+            panel.y + ui.px(ITEM_Y_OFFSET),
+            tint,
             DrawTextureParams {
-                dest_size: Some(vec2(ITEM_WIDTH, ITEM_HEIGHT)),
+                dest_size: Some(vec2(ui.px(ITEM_WIDTH), ui.px(ITEM_HEIGHT))),
                 ..Default::default()
             },
         );
 
-        draw_text(
-            &Weapon::from_index(index).unwrap().to_string(),
-            camera_position.x + RIGHT_X_0S + TEXT_X_OFFSET + offset_position.x,
-            camera_position.y + Y_OS + TEXT_Y_OFFSET + offset_position.y,
+        let text_x = panel.x + ui.px(TEXT_X_OFFSET);
+        ui.text(
+            name,
+            vec2(text_x, panel.y + ui.px(TEXT_Y_OFFSET)),
             24.0,
             WHITE,
         );
+
+        if let Some((count, color)) = count {
+            ui.text(
+                &count,
+                vec2(text_x, panel.y + ui.px(COUNT_Y_OFFSET)),
+                24.0,
+                color,
+            );
+        }
     }
 }
 
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Item {
     Empty = 0,
     Cigs = 1,
+    Goggles = 2,
+    GasMask = 3,
+    Ration = 4,
+    Card1 = 5,
+    Card2 = 6,
+    BodyArmor = 7,
+    Binoculars = 8,
+    CardboardBox = 9,
+    MineDetector = 10,
+    Antidote = 11,
+    Flashlight = 12,
+    Uniform = 13,
+    Compass = 14,
+    Transmitter = 15,
 }
 
 impl std::fmt::Display for Item {
@@ -253,19 +592,154 @@ impl std::fmt::Display for Item {
         match self {
             Item::Empty => write!(f, "Empty"),
             Item::Cigs => write!(f, "Cigs"),
+            Item::Goggles => write!(f, "Goggles"),
+            Item::GasMask => write!(f, "Gas Mask"),
+            Item::Ration => write!(f, "Ration"),
+            Item::Card1 => write!(f, "Card 1"),
+            Item::Card2 => write!(f, "Card 2"),
+            Item::BodyArmor => write!(f, "Body Armor"),
+            Item::Binoculars => write!(f, "Binoculars"),
+            Item::CardboardBox => write!(f, "Cardboard Box"),
+            Item::MineDetector => write!(f, "Mine Detector"),
+            Item::Antidote => write!(f, "Antidote"),
+            Item::Flashlight => write!(f, "Flashlight"),
+            Item::Uniform => write!(f, "Uniform"),
+            Item::Compass => write!(f, "Compass"),
+            Item::Transmitter => write!(f, "Transmitter"),
         }
     }
 }
 
 impl Item {
+    // In menu order, the menu is sized from this
+    pub const ALL: [Item; 16] = [
+        Item::Empty,
+        Item::Cigs,
+        Item::Goggles,
+        Item::GasMask,
+        Item::Ration,
+        Item::Card1,
+        Item::Card2,
+        Item::BodyArmor,
+        Item::Binoculars,
+        Item::CardboardBox,
+        Item::MineDetector,
+        Item::Antidote,
+        Item::Flashlight,
+        Item::Uniform,
+        Item::Compass,
+        Item::Transmitter,
+    ];
+
     pub fn from_index(index: usize) -> Option<Self> {
-        match index {
-            0 => Some(Item::Empty),
-            1 => Some(Item::Cigs),
+        Item::ALL.get(index).copied()
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "empty" => Some(Item::Empty),
+            "cigs" => Some(Item::Cigs),
+            "goggles" => Some(Item::Goggles),
+            "gas mask" => Some(Item::GasMask),
+            "ration" => Some(Item::Ration),
+            "card 1" => Some(Item::Card1),
+            "card 2" => Some(Item::Card2),
+            "body armor" => Some(Item::BodyArmor),
+            "binoculars" => Some(Item::Binoculars),
+            "cardboard box" => Some(Item::CardboardBox),
+            "mine detector" => Some(Item::MineDetector),
+            "antidote" => Some(Item::Antidote),
+            "flashlight" => Some(Item::Flashlight),
+            "uniform" => Some(Item::Uniform),
+            "compass" => Some(Item::Compass),
+            "transmitter" => Some(Item::Transmitter),
+            _ => None,
+        }
+    }
+
+    // What from_name reads back, for saves
+    pub fn name(&self) -> &'static str {
+        match self {
+            Item::Empty => "empty",
+            Item::Cigs => "cigs",
+            Item::Goggles => "goggles",
+            Item::GasMask => "gas mask",
+            Item::Ration => "ration",
+            Item::Card1 => "card 1",
+            Item::Card2 => "card 2",
+            Item::BodyArmor => "body armor",
+            Item::Binoculars => "binoculars",
+            Item::CardboardBox => "cardboard box",
+            Item::MineDetector => "mine detector",
+            Item::Antidote => "antidote",
+            Item::Flashlight => "flashlight",
+            Item::Uniform => "uniform",
+            Item::Compass => "compass",
+            Item::Transmitter => "transmitter",
+        }
+    }
+
+    // Column and row on items.png for items without their own icon file
+    pub fn sheet_cell(&self) -> Option<(u32, u32)> {
+        match self {
+            Item::Goggles => Some((3, 1)),
+            Item::GasMask => Some((3, 3)),
+            Item::Ration => Some((1, 1)),
+            Item::Card1 | Item::Card2 => Some((3, 4)),
+            Item::BodyArmor => Some((0, 3)),
+            Item::Binoculars => Some((1, 4)),
+            Item::CardboardBox => Some((2, 1)),
+            Item::MineDetector => Some((2, 3)),
+            Item::Antidote => Some((4, 1)),
+            Item::Flashlight => Some((0, 0)),
+            Item::Uniform => Some((4, 3)),
+            Item::Compass => Some((4, 0)),
+            Item::Transmitter => Some((3, 2)),
             _ => None,
         }
     }
 
+    pub fn description(&self) -> &'static str {
+        match self {
+            Item::Empty => "Nothing in hand.",
+            Item::Cigs => "Smoke hides Snake's scent from dogs. Bad for the health.",
+            Item::Goggles => "Infrared. Shows laser tripwires.",
+            Item::GasMask => "Keeps the gas out.",
+            Item::Ration => "Eaten the moment life runs out, if it's in hand.",
+            Item::Card1 => "Opens level 1 doors.",
+            Item::Card2 => "Opens level 2 doors.",
+            Item::BodyArmor => "Halves the damage from every hit.",
+            Item::Binoculars => "Stand still to look into the next room.",
+            Item::CardboardBox => "Guards pass right by, as long as it stays still.",
+            Item::MineDetector => "Shows buried mines.",
+            Item::Antidote => "Cures poison.",
+            Item::Flashlight => "Lights up dark rooms.",
+            Item::Uniform => "Guards only look twice up close.",
+            Item::Compass => "Points the way to the next target.",
+            Item::Transmitter => "Someone is listening. Press X to throw it away.",
+        }
+    }
+
+    // What it does while Snake has it
+    pub fn effect(&self) -> &'static dyn ItemEffect {
+        match self {
+            Item::Empty | Item::Card1 | Item::Card2 => &itemeffect::Nothing,
+            Item::Cigs => &itemeffect::Cigs,
+            Item::Goggles => &itemeffect::Goggles,
+            Item::GasMask => &itemeffect::GasMask,
+            Item::Ration => &itemeffect::Ration,
+            Item::BodyArmor => &itemeffect::BodyArmor,
+            Item::Binoculars => &itemeffect::Binoculars,
+            Item::CardboardBox => &itemeffect::CardboardBox,
+            Item::MineDetector => &itemeffect::MineDetector,
+            Item::Antidote => &itemeffect::Antidote,
+            Item::Flashlight => &itemeffect::Flashlight,
+            Item::Uniform => &itemeffect::Uniform,
+            Item::Compass => &itemeffect::Compass,
+            Item::Transmitter => &itemeffect::Transmitter,
+        }
+    }
+
     // pub fn index(&self) -> usize {
     //     match self {
     //         Item::Empty => 0,
@@ -274,10 +748,26 @@ impl Item {
     // }
 }
 
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Weapon {
     Empty = 0,
     Handgun = 1,
+    SubmachineGun = 2,
+    GrenadeLauncher = 3,
+    RocketLauncher = 4,
+    RemoteMissile = 5,
+    PlasticExplosive = 6,
+    LandMine = 7,
+    Silencer = 8,
+}
+
+// How a weapon fires: what comes out, how often, and how loud
+#[derive(Debug, Clone, Copy)]
+pub struct WeaponSpec {
+    pub projectile: Option<Projectile>,
+    pub cooldown: Duration,
+    pub automatic: bool,
+    pub noise: Option<NoiseKind>,
 }
 
 impl std::fmt::Display for Weapon {
@@ -285,15 +775,142 @@ impl std::fmt::Display for Weapon {
         match self {
             Weapon::Empty => write!(f, "Empty"),
             Weapon::Handgun => write!(f, "Handgun"),
+            Weapon::SubmachineGun => write!(f, "SMG"),
+            Weapon::GrenadeLauncher => write!(f, "Grenades"),
+            Weapon::RocketLauncher => write!(f, "Rockets"),
+            Weapon::RemoteMissile => write!(f, "Remote"),
+            Weapon::PlasticExplosive => write!(f, "Plastic"),
+            Weapon::LandMine => write!(f, "Mines"),
+            Weapon::Silencer => write!(f, "Silencer"),
         }
     }
 }
 
 impl Weapon {
+    // In menu order, the menu is sized from this
+    pub const ALL: [Weapon; 9] = [
+        Weapon::Empty,
+        Weapon::Handgun,
+        Weapon::SubmachineGun,
+        Weapon::GrenadeLauncher,
+        Weapon::RocketLauncher,
+        Weapon::RemoteMissile,
+        Weapon::PlasticExplosive,
+        Weapon::LandMine,
+        Weapon::Silencer,
+    ];
+
     pub fn from_index(index: usize) -> Option<Self> {
-        match index {
-            0 => Some(Weapon::Empty),
-            1 => Some(Weapon::Handgun),
+        Weapon::ALL.get(index).copied()
+    }
+
+    // Empty hands mean fists
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "punch" | "empty" => Some(Weapon::Empty),
+            "handgun" => Some(Weapon::Handgun),
+            "submachine gun" => Some(Weapon::SubmachineGun),
+            "grenade launcher" => Some(Weapon::GrenadeLauncher),
+            "rocket launcher" => Some(Weapon::RocketLauncher),
+            "remote missile" => Some(Weapon::RemoteMissile),
+            "plastic explosive" => Some(Weapon::PlasticExplosive),
+            "land mine" => Some(Weapon::LandMine),
+            "silencer" => Some(Weapon::Silencer),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weapon::Empty => "empty",
+            Weapon::Handgun => "handgun",
+            Weapon::SubmachineGun => "submachine gun",
+            Weapon::GrenadeLauncher => "grenade launcher",
+            Weapon::RocketLauncher => "rocket launcher",
+            Weapon::RemoteMissile => "remote missile",
+            Weapon::PlasticExplosive => "plastic explosive",
+            Weapon::LandMine => "land mine",
+            Weapon::Silencer => "silencer",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Weapon::Empty => "Bare hands. Close enough to punch.",
+            Weapon::Handgun => "Loud. Every guard nearby will hear it.",
+            Weapon::SubmachineGun => "Hold fire to keep shooting.",
+            Weapon::GrenadeLauncher => "Lobs a grenade that goes off where it lands.",
+            Weapon::RocketLauncher => "Takes down what bullets can't.",
+            Weapon::RemoteMissile => "Steer it once it's fired.",
+            Weapon::PlasticExplosive => "Set it down and stand well back.",
+            Weapon::LandMine => "Buried where Snake stands, for whoever walks by next.",
+            Weapon::Silencer => "A handgun nobody hears.",
+        }
+    }
+
+    pub fn spec(&self) -> WeaponSpec {
+        let (projectile, cooldown, automatic, noise) = match self {
+            Weapon::Empty => (None, 0, false, None),
+            Weapon::Handgun => (
+                Some(Projectile::Bullet),
+                300,
+                false,
+                Some(NoiseKind::Gunshot),
+            ),
+            Weapon::SubmachineGun => (
+                Some(Projectile::Bullet),
+                100,
+                true,
+                Some(NoiseKind::Gunshot),
+            ),
+            Weapon::GrenadeLauncher => (
+                Some(Projectile::Grenade),
+                900,
+                false,
+                Some(NoiseKind::Gunshot),
+            ),
+            Weapon::RocketLauncher => (
+                Some(Projectile::Missile),
+                1200,
+                false,
+                Some(NoiseKind::Gunshot),
+            ),
+            Weapon::RemoteMissile => (
+                Some(Projectile::Remote),
+                1500,
+                false,
+                Some(NoiseKind::Gunshot),
+            ),
+            Weapon::PlasticExplosive => (Some(Projectile::Charge), 1000, false, None),
+            Weapon::LandMine => (Some(Projectile::Mine), 1000, false, None),
+            // A handgun that doesn't carry
+            Weapon::Silencer => (Some(Projectile::Bullet), 300, false, None),
+        };
+
+        WeaponSpec {
+            projectile,
+            cooldown: Duration::from_millis(cooldown),
+            automatic,
+            noise,
+        }
+    }
+
+    // Cell on weapons.png for weapons without their own icon file
+    pub fn sheet_cell(&self) -> Option<(u32, u32)> {
+        match self {
+            Weapon::SubmachineGun => Some((1, 0)),
+            Weapon::GrenadeLauncher => Some((0, 1)),
+            Weapon::RocketLauncher => Some((1, 1)),
+            _ => None,
+        }
+    }
+
+    // Loose sprite to centre on the icon otherwise
+    pub fn sprite(&self) -> Option<&'static str> {
+        match self {
+            Weapon::RemoteMissile => Some("assets/weapons/missile.png"),
+            Weapon::PlasticExplosive => Some("assets/weapons/mortar.png"),
+            Weapon::LandMine => Some("assets/weapons/mine.png"),
             _ => None,
         }
     }
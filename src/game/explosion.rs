@@ -0,0 +1,79 @@
+use macroquad::prelude::*;
+
+use crate::game::Blast;
+use crate::game::Settings;
+
+#[derive(Debug, Clone)]
+pub struct Explosion {
+    settings: Settings,
+    texture: Texture2D,
+    timer: f32,
+    pub blast: Blast,
+    pub done: bool,
+}
+
+// Frame Size
+const FS_EXPLOSION: Vec2 = Vec2::new(32.0, 32.0);
+
+// Max Frames
+const MF_EXPLOSION: u32 = 4;
+
+// Conts
+const FRAME_TIME: f32 = 0.08;
+
+impl Explosion {
+    pub async fn new(settings: Settings, blast: Blast) -> Self {
+        // Load Textures
+        let texture = load_texture("assets/effects/explosion.png").await.unwrap();
+        texture.set_filter(FilterMode::Nearest);
+
+        Self {
+            settings,
+            texture,
+            timer: 0.0,
+            blast,
+            done: false,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.settings.update();
+
+        self.timer += delta_time;
+        if self.timer >= FRAME_TIME * MF_EXPLOSION as f32 {
+            self.done = true;
+        }
+    }
+
+    pub fn draw(&self) {
+        if self.done {
+            return;
+        }
+
+        let frame = ((self.timer / FRAME_TIME) as u32).min(MF_EXPLOSION - 1);
+        let src_rect = Rect::new(
+            FS_EXPLOSION.x * frame as f32,
+            0.0,
+            FS_EXPLOSION.x,
+            FS_EXPLOSION.y,
+        );
+
+        let origin = self.blast.origin;
+        let radius = self.blast.radius;
+        draw_texture_ex(
+            &self.texture,
+            origin.x - radius,
+            origin.y - radius,
+            WHITE,
+            DrawTextureParams {
+                source: Some(src_rect),
+                dest_size: Some(Vec2::splat(radius * 2.0)),
+                ..Default::default()
+            },
+        );
+
+        if self.settings.debug {
+            draw_circle_lines(origin.x, origin.y, radius, 2.0, ORANGE);
+        }
+    }
+}
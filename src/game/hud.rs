@@ -0,0 +1,190 @@
+use macroquad::prelude::*;
+
+use crate::game::{Alert, Player, Settings, StatusKind};
+use crate::game::{Anchor, Ui};
+
+use super::equipmenu::{Item, Weapon};
+use super::inventory::{MAX_RANK, RESCUES_PER_RANK};
+use super::player::BASE_HEALTH;
+
+// Life, rank, what's equipped and the alert, over everything else
+#[derive(Debug, Clone)]
+pub struct Hud {
+    settings: Settings,
+}
+
+// Layout
+const MARGIN: f32 = 24.0;
+const FONT_SIZE: f32 = 24.0;
+const LABEL_WIDTH: f32 = 72.0;
+const LIFE_SIZE: Vec2 = Vec2::new(256.0, 16.0);
+const LIFE_LOW: f32 = 0.25;
+const ALERT_SIZE: Vec2 = Vec2::new(160.0, 8.0);
+const ICON_SIZE: Vec2 = Vec2::new(140.0, 68.0);
+
+const LIFE_COLOR: Color = Color::new(0.2, 0.8, 0.3, 1.0);
+const POISON_COLOR: Color = Color::new(0.6, 0.3, 0.8, 1.0);
+
+impl Hud {
+    pub fn new(settings: Settings) -> Self {
+        Self { settings }
+    }
+
+    pub fn update(&mut self) {
+        self.settings.update();
+    }
+
+    pub fn draw(&self, ui: &Ui, player: &Player, alert: &Alert) {
+        self.draw_life(ui, player);
+        self.draw_rank(ui, player);
+        self.draw_alert(ui, alert);
+
+        // The menu shows its own while it's open
+        if !player.equip_menu.pause {
+            self.draw_equipped(ui, player);
+        }
+    }
+
+    fn draw_life(&self, ui: &Ui, player: &Player) {
+        let label = ui.rect(Anchor::TopLeft, vec2(MARGIN, MARGIN), LIFE_SIZE);
+        ui.text("LIFE", label.point() + vec2(0.0, label.h), FONT_SIZE, WHITE);
+
+        // The bar grows with rank
+        let max_health = player.max_health();
+        let mut bar = label.offset(vec2(ui.px(LABEL_WIDTH), 0.0));
+        bar.w *= max_health / BASE_HEALTH;
+        let ratio = (player.health / max_health).clamp(0.0, 1.0);
+        let color = if player.statuses.has(StatusKind::Poisoned) {
+            POISON_COLOR
+        } else if ratio <= LIFE_LOW {
+            RED
+        } else {
+            LIFE_COLOR
+        };
+
+        draw_rectangle(bar.x, bar.y, bar.w, bar.h, Color::new(0.0, 0.0, 0.0, 0.6));
+        draw_rectangle(bar.x, bar.y, bar.w * ratio, bar.h, color);
+        draw_rectangle_lines(bar.x, bar.y, bar.w, bar.h, ui.px(2.0), WHITE);
+
+        if self.settings.debug {
+            let text = format!("{:.0}", player.health);
+            let position = vec2(bar.right() + ui.px(8.0), bar.bottom());
+            ui.text(&text, position, FONT_SIZE, WHITE);
+        }
+    }
+
+    fn draw_rank(&self, ui: &Ui, player: &Player) {
+        let offset = vec2(MARGIN, MARGIN * 2.0 + LIFE_SIZE.y);
+        let label = ui.rect(Anchor::TopLeft, offset, LIFE_SIZE);
+        let baseline = label.point() + vec2(0.0, label.h);
+        ui.text("RANK", baseline, FONT_SIZE, WHITE);
+
+        // Earned stars lit, the rest still to go
+        let rank = player.equip_menu.inventory.rank();
+        for star in 0..=MAX_RANK {
+            let color = if star <= rank { YELLOW } else { DARKGRAY };
+            let x = ui.px(LABEL_WIDTH + star as f32 * FONT_SIZE);
+            ui.text("*", baseline + vec2(x, ui.px(4.0)), FONT_SIZE * 1.5, color);
+        }
+
+        // Prisoners towards the next one
+        if rank < MAX_RANK {
+            let text = format!(
+                "POW {}/{}",
+                player.equip_menu.inventory.rescues(),
+                RESCUES_PER_RANK
+            );
+            let x = ui.px(LABEL_WIDTH + (MAX_RANK + 1) as f32 * FONT_SIZE);
+            ui.text(&text, baseline + vec2(x, 0.0), FONT_SIZE, GRAY);
+        }
+    }
+
+    fn draw_alert(&self, ui: &Ui, alert: &Alert) {
+        if !alert.is_active() {
+            return;
+        }
+
+        // Blinks while the countdown runs
+        let bar = ui.rect(
+            Anchor::TopRight,
+            vec2(MARGIN, MARGIN + FONT_SIZE),
+            ALERT_SIZE,
+        );
+        if (get_time() * 2.0).fract() < 0.5 {
+            ui.text("ALERT", bar.point() - vec2(0.0, ui.px(4.0)), FONT_SIZE, RED);
+        }
+
+        draw_rectangle(bar.x, bar.y, bar.w * alert.time_left(), bar.h, RED);
+    }
+
+    fn draw_equipped(&self, ui: &Ui, player: &Player) {
+        let equip_menu = &player.equip_menu;
+        let offset = vec2(MARGIN, MARGIN + FONT_SIZE);
+
+        let item = Item::from_index(equip_menu.left_selected).unwrap_or(Item::Empty);
+        if item != Item::Empty {
+            let count = equip_menu
+                .inventory
+                .count(item)
+                .map(|(count, _)| (count.to_string(), count == 0));
+            let slot = ui.rect(Anchor::BottomLeft, offset, ICON_SIZE);
+            self.draw_slot(
+                ui,
+                &equip_menu.item_icon(item),
+                &item.to_string(),
+                count,
+                slot,
+            );
+        }
+
+        let weapon = Weapon::from_index(equip_menu.right_selected).unwrap_or(Weapon::Empty);
+        if weapon != Weapon::Empty {
+            let ammo = equip_menu
+                .inventory
+                .ammo(weapon)
+                .map(|(ammo, capacity)| (format!("{}/{}", ammo, capacity), ammo == 0));
+            let slot = ui.rect(Anchor::BottomRight, offset, ICON_SIZE);
+            self.draw_slot(
+                ui,
+                &equip_menu.weapon_icon(weapon),
+                &weapon.to_string(),
+                ammo,
+                slot,
+            );
+        }
+    }
+
+    // Icon with its name under it, the count goes red when it runs out
+    fn draw_slot(
+        &self,
+        ui: &Ui,
+        icon: &Texture2D,
+        name: &str,
+        count: Option<(String, bool)>,
+        slot: Rect,
+    ) {
+        draw_texture_ex(
+            icon,
+            slot.x,
+            slot.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(slot.size()),
+                ..Default::default()
+            },
+        );
+
+        ui.text(
+            name,
+            vec2(slot.x, slot.bottom() + ui.px(FONT_SIZE)),
+            FONT_SIZE,
+            WHITE,
+        );
+
+        if let Some((count, empty)) = count {
+            let width = ui.text_width(&count, FONT_SIZE);
+            let position = vec2(slot.right() - width, slot.y + ui.px(FONT_SIZE * 0.6));
+            ui.text(&count, position, FONT_SIZE, if empty { RED } else { WHITE });
+        }
+    }
+}
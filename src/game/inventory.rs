@@ -0,0 +1,229 @@
+use super::equipmenu::{Item, Weapon};
+
+// What Snake is carrying. Ammo and consumables are capped by capacity,
+// which grows with rank.
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    rank: u32,
+    // Prisoners freed since the last rank
+    rescues: u32,
+    weapons: Vec<bool>,
+    ammo: Vec<u32>,
+    items: Vec<bool>,
+    counts: Vec<u32>,
+}
+
+pub const MAX_RANK: u32 = 4;
+pub const RESCUES_PER_RANK: u32 = 3;
+
+const START_CIGS: u32 = 20;
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        let mut inventory = Inventory::restore(0, 0, &[], &[]);
+
+        // Everything else is found along the way
+        inventory.add_item(Item::Cigs, START_CIGS);
+
+        inventory
+    }
+
+    // Exactly what a save had, the rank first so the capacities fit
+    pub fn restore(
+        rank: u32,
+        rescues: u32,
+        weapons: &[(Weapon, u32)],
+        items: &[(Item, u32)],
+    ) -> Self {
+        let mut inventory = Self {
+            rank: rank.min(MAX_RANK),
+            rescues: rescues % RESCUES_PER_RANK,
+            weapons: vec![false; Weapon::ALL.len()],
+            ammo: vec![0; Weapon::ALL.len()],
+            items: vec![false; Item::ALL.len()],
+            counts: vec![0; Item::ALL.len()],
+        };
+
+        // Empty hands are always an option
+        inventory.weapons[Weapon::Empty as usize] = true;
+        inventory.items[Item::Empty as usize] = true;
+
+        for (weapon, ammo) in weapons {
+            inventory.add_weapon(*weapon, *ammo);
+        }
+        for (item, count) in items {
+            inventory.add_item(*item, *count);
+        }
+
+        inventory
+    }
+
+    // Owned weapons with the rounds in their own pool, for saves
+    pub fn weapons(&self) -> Vec<(Weapon, u32)> {
+        Weapon::ALL
+            .iter()
+            .filter(|weapon| **weapon != Weapon::Empty && self.owns_weapon(**weapon))
+            .map(|weapon| (*weapon, self.ammo[*weapon as usize]))
+            .collect()
+    }
+
+    pub fn items(&self) -> Vec<(Item, u32)> {
+        Item::ALL
+            .iter()
+            .filter(|item| **item != Item::Empty && self.owns_item(**item))
+            .map(|item| (*item, self.counts[*item as usize]))
+            .collect()
+    }
+
+    pub fn rank(&self) -> u32 {
+        self.rank
+    }
+
+    pub fn promote(&mut self) {
+        self.rank = (self.rank + 1).min(MAX_RANK);
+    }
+
+    // Killing a prisoner costs a rank and whatever was towards the next
+    pub fn demote(&mut self) {
+        self.rank = self.rank.saturating_sub(1);
+        self.rescues = 0;
+    }
+
+    pub fn rescues(&self) -> u32 {
+        self.rescues
+    }
+
+    // True when it was enough for a rank
+    pub fn rescue(&mut self) -> bool {
+        self.rescues += 1;
+        if self.rescues < RESCUES_PER_RANK {
+            return false;
+        }
+
+        self.rescues = 0;
+        let promoted = self.rank < MAX_RANK;
+        self.promote();
+        promoted
+    }
+
+    // Weapons
+
+    pub fn owns_weapon(&self, weapon: Weapon) -> bool {
+        self.weapons[weapon as usize]
+    }
+
+    pub fn add_weapon(&mut self, weapon: Weapon, ammo: u32) {
+        self.weapons[weapon as usize] = true;
+        self.add_ammo(weapon, ammo);
+    }
+
+    pub fn add_ammo(&mut self, weapon: Weapon, ammo: u32) {
+        if let Some(capacity) = self.weapon_capacity(weapon) {
+            let pool = &mut self.ammo[Inventory::ammo_pool(weapon) as usize];
+            *pool = (*pool + ammo).min(capacity);
+        }
+    }
+
+    // Loaded and capacity, for weapons that take ammo
+    pub fn ammo(&self, weapon: Weapon) -> Option<(u32, u32)> {
+        let capacity = self.weapon_capacity(weapon)?;
+        Some((self.ammo[Inventory::ammo_pool(weapon) as usize], capacity))
+    }
+
+    // Takes a round if there is one, weapons without ammo always fire
+    pub fn use_ammo(&mut self, weapon: Weapon) -> bool {
+        if self.weapon_capacity(weapon).is_none() {
+            return true;
+        }
+
+        let pool = &mut self.ammo[Inventory::ammo_pool(weapon) as usize];
+        if *pool == 0 {
+            return false;
+        }
+        *pool -= 1;
+        true
+    }
+
+    pub fn weapon_capacity(&self, weapon: Weapon) -> Option<u32> {
+        let base = match Inventory::ammo_pool(weapon) {
+            Weapon::Handgun => 50,
+            Weapon::SubmachineGun => 100,
+            Weapon::GrenadeLauncher
+            | Weapon::RocketLauncher
+            | Weapon::RemoteMissile
+            | Weapon::PlasticExplosive
+            | Weapon::LandMine => 10,
+            Weapon::Empty | Weapon::Silencer => return None,
+        };
+        Some(self.with_rank(base))
+    }
+
+    // Items
+
+    pub fn owns_item(&self, item: Item) -> bool {
+        self.items[item as usize]
+    }
+
+    pub fn add_item(&mut self, item: Item, count: u32) {
+        self.items[item as usize] = true;
+        if let Some(capacity) = self.item_capacity(item) {
+            let held = &mut self.counts[item as usize];
+            *held = (*held + count).min(capacity);
+        }
+    }
+
+    pub fn remove_item(&mut self, item: Item) {
+        self.items[item as usize] = false;
+        self.counts[item as usize] = 0;
+    }
+
+    pub fn count(&self, item: Item) -> Option<(u32, u32)> {
+        let capacity = self.item_capacity(item)?;
+        Some((self.counts[item as usize], capacity))
+    }
+
+    // Takes one if there is one, items that aren't used up always work
+    pub fn use_item(&mut self, item: Item) -> bool {
+        if self.item_capacity(item).is_none() {
+            return self.owns_item(item);
+        }
+
+        let held = &mut self.counts[item as usize];
+        if *held == 0 {
+            return false;
+        }
+        *held -= 1;
+        true
+    }
+
+    pub fn item_capacity(&self, item: Item) -> Option<u32> {
+        let base = match item {
+            Item::Cigs => 20,
+            Item::Ration => 2,
+            Item::Antidote => 3,
+            _ => return None,
+        };
+        Some(self.with_rank(base))
+    }
+
+    // Private
+
+    // The silencer screws onto the handgun and shares its rounds
+    fn ammo_pool(weapon: Weapon) -> Weapon {
+        match weapon {
+            Weapon::Silencer => Weapon::Handgun,
+            _ => weapon,
+        }
+    }
+
+    // Each rank adds half the base capacity again
+    fn with_rank(&self, base: u32) -> u32 {
+        base + base / 2 * self.rank
+    }
+}
@@ -0,0 +1,194 @@
+use macroquad::prelude::*;
+
+use super::equipmenu::Item;
+use super::player::Player;
+use super::status::{StatusKind, HELD};
+
+// What an item does for Snake. Everything defaults to doing nothing,
+// each item only says what it changes.
+pub trait ItemEffect {
+    // Called by the equip flow when the left slot changes
+    fn equip(&self, _player: &mut Player) {}
+    fn unequip(&self, _player: &mut Player) {}
+
+    // Every frame the item is equipped
+    fn update(&self, _player: &mut Player) {}
+
+    fn speed_scale(&self) -> f32 {
+        1.0
+    }
+
+    // How much of their range guards can spot Snake at, 0 is never
+    fn visibility(&self, _moving: bool) -> f32 {
+        1.0
+    }
+
+    // Drawn in place of Snake
+    fn covers_snake(&self) -> bool {
+        false
+    }
+
+    fn filters_gas(&self) -> bool {
+        false
+    }
+
+    fn sees_lasers(&self) -> bool {
+        false
+    }
+
+    fn reveals_mines(&self) -> bool {
+        false
+    }
+
+    fn lights_dark(&self) -> bool {
+        false
+    }
+
+    // Camera looks into the next room
+    fn peeks(&self) -> bool {
+        false
+    }
+
+    fn points_to_objective(&self) -> bool {
+        false
+    }
+
+    // Works from the bag, it doesn't have to be equipped
+    fn transmits(&self) -> bool {
+        false
+    }
+}
+
+// Conts
+const BOX_SPEED: f32 = 0.6;
+const BOX_MOVING_VISIBILITY: f32 = 0.4;
+
+pub struct Nothing;
+pub struct Cigs;
+pub struct Goggles;
+pub struct GasMask;
+pub struct Ration;
+pub struct BodyArmor;
+pub struct Binoculars;
+pub struct CardboardBox;
+pub struct MineDetector;
+pub struct Antidote;
+pub struct Flashlight;
+pub struct Uniform;
+pub struct Compass;
+pub struct Transmitter;
+
+impl ItemEffect for Nothing {}
+
+impl ItemEffect for Cigs {
+    fn update(&self, player: &mut Player) {
+        if player.is_smoking() {
+            player.smoke();
+        }
+    }
+}
+
+impl ItemEffect for Goggles {
+    fn sees_lasers(&self) -> bool {
+        true
+    }
+}
+
+impl ItemEffect for GasMask {
+    fn filters_gas(&self) -> bool {
+        true
+    }
+}
+
+impl ItemEffect for Ration {
+    // Eaten the moment health runs out
+    fn update(&self, player: &mut Player) {
+        if player.health <= 0.0 && player.equip_menu.inventory.use_item(Item::Ration) {
+            player.health = player.max_health();
+        }
+    }
+}
+
+impl ItemEffect for BodyArmor {
+    fn update(&self, player: &mut Player) {
+        player.statuses.apply(StatusKind::Armored, HELD);
+    }
+}
+
+impl ItemEffect for Binoculars {
+    fn peeks(&self) -> bool {
+        true
+    }
+}
+
+impl ItemEffect for CardboardBox {
+    // Snake ducks in, whatever he was doing stops
+    fn equip(&self, player: &mut Player) {
+        player.stand();
+    }
+
+    fn speed_scale(&self) -> f32 {
+        BOX_SPEED
+    }
+
+    // Just another box until it starts walking
+    fn visibility(&self, moving: bool) -> f32 {
+        if moving {
+            BOX_MOVING_VISIBILITY
+        } else {
+            0.0
+        }
+    }
+
+    fn covers_snake(&self) -> bool {
+        true
+    }
+}
+
+impl ItemEffect for MineDetector {
+    fn reveals_mines(&self) -> bool {
+        true
+    }
+}
+
+impl ItemEffect for Antidote {
+    fn update(&self, player: &mut Player) {
+        if player.statuses.has(StatusKind::Poisoned)
+            && player.equip_menu.inventory.use_item(Item::Antidote)
+        {
+            player.statuses.remove(StatusKind::Poisoned);
+        }
+    }
+}
+
+impl ItemEffect for Flashlight {
+    fn lights_dark(&self) -> bool {
+        true
+    }
+}
+
+impl ItemEffect for Uniform {
+    fn update(&self, player: &mut Player) {
+        player.statuses.apply(StatusKind::Disguised, HELD);
+    }
+}
+
+impl ItemEffect for Compass {
+    fn points_to_objective(&self) -> bool {
+        true
+    }
+}
+
+impl ItemEffect for Transmitter {
+    // The only way to stop it is to throw it away
+    fn update(&self, player: &mut Player) {
+        if is_key_pressed(KeyCode::X) {
+            player.equip_menu.inventory.remove_item(Item::Transmitter);
+            player.equip_menu.equip_item(Item::Empty);
+        }
+    }
+
+    fn transmits(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,52 @@
+use macroquad::prelude::*;
+
+use crate::game::Settings;
+
+// Infrared beam, only visible through goggles
+#[derive(Debug, Clone)]
+pub struct Laser {
+    settings: Settings,
+    pub room: String,
+    pub beam: Rect,
+}
+
+const THICKNESS: f32 = 4.0;
+
+impl Laser {
+    // The beam runs down the middle of the area, along its long side
+    pub fn new(settings: Settings, area: Rect, room: &str) -> Self {
+        let beam = if area.w >= area.h {
+            Rect::new(area.x, area.center().y - THICKNESS / 2.0, area.w, THICKNESS)
+        } else {
+            Rect::new(area.center().x - THICKNESS / 2.0, area.y, THICKNESS, area.h)
+        };
+
+        Self {
+            settings,
+            room: room.to_string(),
+            beam,
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.settings.update();
+    }
+
+    pub fn crossed_by(&self, collider: Rect) -> bool {
+        self.beam.overlaps(&collider)
+    }
+
+    pub fn draw(&self, goggles: bool) {
+        if !goggles && !self.settings.debug {
+            return;
+        }
+
+        draw_rectangle(
+            self.beam.x,
+            self.beam.y,
+            self.beam.w,
+            self.beam.h,
+            Color::new(1.0, 0.0, 0.0, 0.8),
+        );
+    }
+}
@@ -1,18 +1,24 @@
 use macroquad::prelude::*;
 
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{ BufRead, BufReader };
 
 use crate::game::Settings;
 use crate::game::Room;
+use crate::game::{ Spawn, SpawnKind };
+use crate::game::Respawn;
 
 pub struct Map {
     settings: Settings,
     pub rooms: Vec<Room>,
+    pub spawns: Vec<Spawn>,
+    // Room index by grid slot, for lookups by position
+    cells: HashMap<(i32, i32), usize>,
 }
 
 impl Map {
-    pub async fn new(settings: Settings, map_file: &str) -> Self {
+    pub async fn new(settings: Settings, map_file: &str, spawn_file: &str) -> Self {
         // Load all the room textures and collision maps here
         let mut rooms = Vec::new();
 
@@ -38,18 +44,52 @@ impl Map {
         for (i, row) in map_grid.iter().enumerate() {
             for (j, room) in row.iter().enumerate() {
                 if let Some(room_id) = room {
-                    let r = Room::new(&room_id, Vec2 { x: j as f32, y: i as f32 }).await;
+                    let r = Room::new(room_id, Vec2 { x: j as f32, y: i as f32 }).await;
                     rooms.push(r);
                 }
             }
         }
 
+        // The first room placed in a slot wins, as a search through the list would
+        let mut cells = HashMap::new();
+        for (index, room) in rooms.iter().enumerate() {
+            cells.entry(Room::cell_at(room.bounds.center())).or_insert(index);
+        }
+
+        let spawns = Spawn::load(spawn_file);
+
+        // Room flags and areas live on the room itself
+        for spawn in &spawns {
+            if let Some(room) = rooms.iter_mut().find(|room| room.name == spawn.room) {
+                match spawn.kind {
+                    SpawnKind::Floor => room.noisy_floors.push(spawn.area(room.bounds)),
+                    SpawnKind::Gas => room.gas = true,
+                    SpawnKind::Dark => room.dark = true,
+                    SpawnKind::Respawn => room.respawn = Respawn::from_args(&spawn.args),
+                    _ => {}
+                }
+            }
+        }
+
         Map {
             settings,
             rooms,
+            spawns,
+            cells,
         }
     }
 
+    pub fn find_room(&self, name: &str) -> Option<&Room> {
+        self.rooms.iter().find(|room| room.name == name)
+    }
+
+    pub fn room_at(&self, point: Vec2) -> Option<&Room> {
+        self.cells
+            .get(&Room::cell_at(point))
+            .map(|&index| &self.rooms[index])
+            .filter(|room| room.bounds.contains(point))
+    }
+
     pub fn draw(&mut self) {
         self.settings.update();
         // Draw all the rooms in the map grid
@@ -0,0 +1,112 @@
+use macroquad::prelude::*;
+
+use crate::game::Settings;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseKind {
+    Footstep,
+    Gunshot,
+    Punch,
+    Explosion,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Noise {
+    pub kind: NoiseKind,
+    pub origin: Vec2,
+    pub radius: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct NoiseBus {
+    settings: Settings,
+    pending: Vec<Noise>,
+    rings: Vec<(Noise, f32)>,
+}
+
+// Each wall between a noise and a listener halves how far it carries
+const WALL_DAMPING: f32 = 0.5;
+const RING_LIFE: f32 = 0.5;
+
+impl NoiseKind {
+    pub fn radius(&self) -> f32 {
+        match self {
+            NoiseKind::Footstep => 192.0,
+            NoiseKind::Gunshot => 768.0,
+            NoiseKind::Punch => 384.0,
+            NoiseKind::Explosion => 1024.0,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            NoiseKind::Footstep => SKYBLUE,
+            NoiseKind::Gunshot => RED,
+            NoiseKind::Punch => YELLOW,
+            NoiseKind::Explosion => ORANGE,
+        }
+    }
+}
+
+impl Noise {
+    pub fn new(kind: NoiseKind, origin: Vec2) -> Self {
+        Self {
+            kind,
+            origin,
+            radius: kind.radius(),
+        }
+    }
+
+    pub fn heard_at(&self, point: Vec2, walls: u32) -> bool {
+        let radius = self.radius * WALL_DAMPING.powi(walls as i32);
+        self.origin.distance(point) <= radius
+    }
+}
+
+impl NoiseBus {
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            settings,
+            pending: Vec::new(),
+            rings: Vec::new(),
+        }
+    }
+
+    pub fn emit(&mut self, noise: Noise) {
+        self.pending.push(noise);
+    }
+
+    // Hand over everything emitted since the last drain
+    pub fn drain(&mut self) -> Vec<Noise> {
+        let noises = std::mem::take(&mut self.pending);
+        for noise in &noises {
+            self.rings.push((*noise, 0.0));
+        }
+        noises
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.settings.update();
+
+        for (_, age) in self.rings.iter_mut() {
+            *age += delta_time;
+        }
+        self.rings.retain(|(_, age)| *age < RING_LIFE);
+    }
+
+    pub fn draw(&self) {
+        if !self.settings.debug {
+            return;
+        }
+
+        for (noise, age) in &self.rings {
+            draw_circle_lines(
+                noise.origin.x,
+                noise.origin.y,
+                noise.radius * (age / RING_LIFE),
+                2.0,
+                noise.kind.color(),
+            );
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use macroquad::prelude::*;
+
+use std::collections::VecDeque;
+
+use crate::game::{Anchor, Ui};
+
+// Short messages across the top of the screen, shown one after another
+#[derive(Debug, Clone)]
+pub struct Notification {
+    queue: VecDeque<String>,
+    timer: f32,
+}
+
+const SHOW_TIME: f32 = 2.0;
+const FADE_TIME: f32 = 0.5;
+const FONT_SIZE: f32 = 32.0;
+// Down from the top, as a percentage of the screen
+const TOP_PERCENT: f32 = 15.0;
+
+impl Default for Notification {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Notification {
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            timer: 0.0,
+        }
+    }
+
+    pub fn push(&mut self, text: String) {
+        if self.queue.is_empty() {
+            self.timer = 0.0;
+        }
+        self.queue.push_back(text);
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        if self.queue.is_empty() {
+            return;
+        }
+
+        self.timer += delta_time;
+        if self.timer >= SHOW_TIME {
+            self.timer = 0.0;
+            self.queue.pop_front();
+        }
+    }
+
+    pub fn draw(&self, ui: &Ui) {
+        let text = match self.queue.front() {
+            Some(text) => text,
+            None => return,
+        };
+
+        let alpha = ((SHOW_TIME - self.timer) / FADE_TIME).clamp(0.0, 1.0);
+        ui.label(
+            text,
+            Anchor::Top,
+            ui.percent(0.0, TOP_PERCENT),
+            FONT_SIZE,
+            Color::new(1.0, 1.0, 1.0, alpha),
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use macroquad::prelude::*;
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use crate::game::Map;
+
+// A walk through the collider grid, one cell centre at a time
+#[derive(Debug, Clone, Default)]
+pub struct Route {
+    points: VecDeque<Vec2>,
+}
+
+type Cell = (i32, i32);
+
+const CELL_SIZE: f32 = 32.0;
+// Give up rather than stall the frame on an unreachable goal
+const MAX_VISITED: usize = 4096;
+const SQUEEZE: i32 = 4;
+const NEIGHBOURS: [Cell; 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+impl Route {
+    // A* over every room's grid, so routes can run into the neighbours.
+    // Size is the walker's collider, cells it only squeezes into cost more
+    // so routes keep to the middle of corridors.
+    pub fn find(map: &Map, from: Vec2, to: Vec2, size: Vec2) -> Option<Self> {
+        let start = Self::cell_at(from);
+        let goal = Self::cell_at(to);
+        if !Self::walkable(map, Self::centre(goal)) {
+            return None;
+        }
+
+        let mut open = BinaryHeap::new();
+        let mut came_from: HashMap<Cell, Cell> = HashMap::new();
+        let mut cost: HashMap<Cell, i32> = HashMap::new();
+
+        open.push(Reverse((Self::distance(start, goal), start)));
+        cost.insert(start, 0);
+
+        while let Some(Reverse((_, cell))) = open.pop() {
+            if cell == goal {
+                return Some(Self::walk_back(&came_from, cell, to));
+            }
+            if cost.len() > MAX_VISITED {
+                return None;
+            }
+
+            for (dx, dy) in NEIGHBOURS {
+                let next = (cell.0 + dx, cell.1 + dy);
+                if !Self::walkable(map, Self::centre(next)) {
+                    continue;
+                }
+
+                let step = if Self::fits(map, next, size) {
+                    1
+                } else {
+                    SQUEEZE
+                };
+                let next_cost = cost[&cell] + step;
+                if cost.get(&next).is_some_and(|&known| known <= next_cost) {
+                    continue;
+                }
+
+                cost.insert(next, next_cost);
+                came_from.insert(next, cell);
+                open.push(Reverse((next_cost + Self::distance(next, goal), next)));
+            }
+        }
+
+        None
+    }
+
+    pub fn next(&self) -> Option<Vec2> {
+        self.points.front().copied()
+    }
+
+    pub fn advance(&mut self) {
+        self.points.pop_front();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    pub fn draw(&self, from: Vec2, color: Color) {
+        let mut last = from;
+        for point in &self.points {
+            draw_line(last.x, last.y, point.x, point.y, 2.0, color);
+            draw_circle(point.x, point.y, 3.0, color);
+            last = *point;
+        }
+    }
+
+    // Private
+    fn cell_at(point: Vec2) -> Cell {
+        (
+            (point.x / CELL_SIZE).floor() as i32,
+            (point.y / CELL_SIZE).floor() as i32,
+        )
+    }
+
+    fn centre(cell: Cell) -> Vec2 {
+        Vec2::new(
+            (cell.0 as f32 + 0.5) * CELL_SIZE,
+            (cell.1 as f32 + 0.5) * CELL_SIZE,
+        )
+    }
+
+    fn distance(a: Cell, b: Cell) -> i32 {
+        (a.0 - b.0).abs() + (a.1 - b.1).abs()
+    }
+
+    // Stand the collider on the cell centre and check its corners
+    fn fits(map: &Map, cell: Cell, size: Vec2) -> bool {
+        let centre = Self::centre(cell);
+        let half = size / 2.0 - Vec2::ONE;
+        [
+            centre + Vec2::new(-half.x, -half.y),
+            centre + Vec2::new(half.x, -half.y),
+            centre + Vec2::new(-half.x, half.y),
+            centre + Vec2::new(half.x, half.y),
+        ]
+        .iter()
+        .all(|&corner| Self::walkable(map, corner))
+    }
+
+    // Outside every room counts as wall
+    fn walkable(map: &Map, point: Vec2) -> bool {
+        map.room_at(point)
+            .is_some_and(|room| !room.collider_map.is_solid(point))
+    }
+
+    fn walk_back(came_from: &HashMap<Cell, Cell>, goal: Cell, to: Vec2) -> Self {
+        let mut points = VecDeque::new();
+        points.push_front(to);
+
+        let mut cell = goal;
+        while let Some(&previous) = came_from.get(&cell) {
+            points.push_front(Self::centre(previous));
+            cell = previous;
+        }
+
+        // The first centre is the cell the walker already stands in
+        if points.len() > 1 {
+            points.pop_front();
+        }
+
+        Self { points }
+    }
+}
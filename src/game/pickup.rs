@@ -0,0 +1,102 @@
+use macroquad::prelude::*;
+
+use crate::game::spawn;
+use crate::game::Settings;
+
+use super::equipmenu::{Item, Weapon};
+
+// Something lying in a room, collected on touch
+#[derive(Debug, Clone)]
+pub struct Pickup {
+    settings: Settings,
+    icon: Texture2D,
+    pub id: String,
+    pub room: String,
+    pub kind: PickupKind,
+    pub bounds: Rect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PickupKind {
+    Item(Item, u32),
+    Weapon(Weapon, u32),
+    Ammo(Weapon, u32),
+}
+
+// Icons are 35x17, drawn at the size of a couple of cells
+const SIZE: Vec2 = Vec2::new(70.0, 34.0);
+
+impl PickupKind {
+    // Spawn args: item, name[, count] | weapon, name[, ammo] | ammo, weapon name, rounds
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let name = args.get(1)?;
+        let amount = args
+            .get(2)
+            .and_then(|arg| arg.parse::<u32>().ok())
+            .unwrap_or(1);
+
+        match args.first()?.as_str() {
+            "item" => Some(PickupKind::Item(Item::from_name(name)?, amount)),
+            "weapon" => Some(PickupKind::Weapon(Weapon::from_name(name)?, amount)),
+            "ammo" => Some(PickupKind::Ammo(Weapon::from_name(name)?, amount)),
+            _ => None,
+        }
+    }
+
+    // What the notification says
+    pub fn describe(&self) -> String {
+        match self {
+            PickupKind::Item(item, count) if *count > 1 => format!("{} x{}", item, count),
+            PickupKind::Item(item, _) => item.to_string(),
+            PickupKind::Weapon(weapon, _) => weapon.to_string(),
+            PickupKind::Ammo(weapon, rounds) => format!("{} ammo +{}", weapon, rounds),
+        }
+    }
+}
+
+impl Pickup {
+    pub fn new(
+        settings: Settings,
+        kind: PickupKind,
+        position: Vec2,
+        room: &str,
+        icon: Texture2D,
+    ) -> Self {
+        Self {
+            settings,
+            icon,
+            id: spawn::id(room, position),
+            room: room.to_string(),
+            kind,
+            bounds: Rect::new(position.x, position.y, SIZE.x, SIZE.y),
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.settings.update();
+    }
+
+    pub fn draw(&self) {
+        if self.settings.debug {
+            draw_rectangle_lines(
+                self.bounds.x,
+                self.bounds.y,
+                self.bounds.w,
+                self.bounds.h,
+                2.0,
+                GREEN,
+            );
+        }
+
+        draw_texture_ex(
+            &self.icon,
+            self.bounds.x,
+            self.bounds.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(self.bounds.size()),
+                ..Default::default()
+            },
+        );
+    }
+}
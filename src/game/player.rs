@@ -1,13 +1,17 @@
 use macroquad::prelude::*;
 use std::time::Duration;
 
-use crate::game::Bullet;
 use crate::game::Effect;
 use crate::game::EquipMenu;
+use crate::game::Noise;
 use crate::game::Settings;
+use crate::game::{Blast, Bullet, Projectile};
+use crate::game::{StatusEffects, StatusKind};
 
 use super::equipmenu::Item;
 use super::equipmenu::Weapon;
+use super::itemeffect::ItemEffect;
+use super::status::HELD;
 
 #[derive(Debug, Clone)]
 pub struct Player {
@@ -16,11 +20,16 @@ pub struct Player {
     effect: Effect,
     texture: Texture2D,
     textures: [Texture2D; 5],
+    box_texture: Texture2D,
+    worn: Item,
     frame_counter: u32,
     frame_delay: Duration,
     last_frame_update: std::time::Instant,
-    last_effect_update: std::time::Instant,
     last_punch_update: std::time::Instant,
+    last_shot: std::time::Instant,
+    // Seconds the lit cigarette has burned, only while the game runs
+    cig_burnt: f32,
+    punch_spent: bool,
     state: State,
     pub direction: Direction,
     pub position: Vec2,
@@ -29,7 +38,12 @@ pub struct Player {
     pub collider: Rect,
     pub col_arr: [bool; 4],
     pub health: f32,
+    pub statuses: StatusEffects,
     pub bullets: Vec<Bullet>,
+    pub blasts: Vec<Blast>,
+    pub noises: Vec<Noise>,
+    // Sound effects for the game to play, by name
+    pub sounds: Vec<&'static str>,
 }
 
 // Enums
@@ -42,7 +56,7 @@ enum State {
     Punching,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Direction {
     Up,
     Down,
@@ -50,12 +64,57 @@ pub enum Direction {
     Right,
 }
 
+impl Direction {
+    pub fn vector(&self) -> Vec2 {
+        match self {
+            Direction::Down => Vec2::new(0.0, 1.0),
+            Direction::Left => Vec2::new(-1.0, 0.0),
+            Direction::Up => Vec2::new(0.0, -1.0),
+            Direction::Right => Vec2::new(1.0, 0.0),
+        }
+    }
+
+    pub fn from_vector(vector: Vec2) -> Self {
+        if vector.x.abs() > vector.y.abs() {
+            if vector.x < 0.0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            }
+        } else if vector.y < 0.0 {
+            Direction::Up
+        } else {
+            Direction::Down
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            "left" => Some(Direction::Left),
+            "right" => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    pub fn clockwise(&self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+}
+
 // Frame Size
 const FS_STANDING: Vec2 = Vec2::new(17.0, 30.0);
 const FS_STANDING_GUN: Vec2 = Vec2::new(17.0, 30.0);
 const FS_WALKING: Vec2 = Vec2::new(17.0, 30.0);
 const FS_WALKING_GUN: Vec2 = Vec2::new(18.0, 31.0);
 const FS_PUNCHING: Vec2 = Vec2::new(20.0, 34.0);
+const FS_BOX: Vec2 = Vec2::new(16.0, 16.0);
 
 // Max Frames
 const MF_WALKING: u32 = 2;
@@ -67,6 +126,13 @@ const SCALE: f32 = 4.0;
 const SPEED: f32 = 200.0;
 const SHUTTER: u64 = 224;
 const PUNCHING: Duration = Duration::from_millis(1000);
+const PUNCH_REACH: f32 = 40.0;
+const THROW_RANGE: f32 = 256.0;
+// Seconds
+const CIG_LIFE: f32 = 30.0;
+// Each rank adds to the life bar
+pub const BASE_HEALTH: f32 = 100.0;
+const HEALTH_PER_RANK: f32 = 25.0;
 
 // const DEBUG_POS: Vec2 = Vec2::new(512.0 - FS_STANDING.x / 2.0 + 512.0 * 8.0, 384.0 * 4.5);
 
@@ -92,6 +158,18 @@ impl Player {
         let punching_texture = load_texture("assets/snake/punching.png").await.unwrap();
         punching_texture.set_filter(FilterMode::Nearest);
 
+        // No sprite of its own, the box is cut from the item sheet
+        let item_sheet = load_image("assets/items/items.png").await.unwrap();
+        let (col, row) = Item::CardboardBox.sheet_cell().unwrap();
+        let box_image = item_sheet.sub_image(Rect::new(
+            1.0 + col as f32 * (FS_BOX.x + 1.0),
+            1.0 + row as f32 * (FS_BOX.y + 1.0),
+            FS_BOX.x,
+            FS_BOX.y,
+        ));
+        let box_texture = Texture2D::from_image(&box_image);
+        box_texture.set_filter(FilterMode::Nearest);
+
         // Pattern: Up, Down, Left, Right
         let col_arr = [false, false, false, false];
 
@@ -110,6 +188,8 @@ impl Player {
                 walking_gun_texture,
                 punching_texture,
             ],
+            box_texture,
+            worn: Item::Cigs,
             state: State::StandingGun,
             direction: Direction::Up,
             position: START_POS,
@@ -117,8 +197,10 @@ impl Player {
             frame_counter: 0,
             frame_delay: Duration::from_millis(SHUTTER),
             last_frame_update: std::time::Instant::now(),
-            last_effect_update: std::time::Instant::now(),
             last_punch_update: std::time::Instant::now(),
+            last_shot: std::time::Instant::now(),
+            cig_burnt: 0.0,
+            punch_spent: true,
             bounds: Rect::new(
                 START_POS.x,
                 START_POS.y,
@@ -132,8 +214,12 @@ impl Player {
                 FS_STANDING.y * SCALE * 0.5,
             ),
             col_arr,
-            health: 100.0,
+            health: BASE_HEALTH,
+            statuses: StatusEffects::new(),
             bullets,
+            blasts: Vec::new(),
+            noises: Vec::new(),
+            sounds: Vec::new(),
         }
     }
 
@@ -163,7 +249,7 @@ impl Player {
                 }
 
                 if is_key_pressed(KeyCode::P) {
-                    self.state = State::Punching;
+                    self.start_punch();
                 }
             }
 
@@ -183,12 +269,12 @@ impl Player {
                     self.col_arr = [false, false, false, false];
                 }
 
-                if is_key_pressed(KeyCode::F) || is_mouse_button_pressed(MouseButton::Left) {
+                if self.trigger_pulled() {
                     self.fire_weapon().await;
                 }
 
                 if is_key_pressed(KeyCode::P) {
-                    self.state = State::Punching;
+                    self.start_punch();
                 }
             }
 
@@ -299,9 +385,16 @@ impl Player {
             }
         }
 
+        // Left and right steer anything guided
+        let turn = is_key_down(KeyCode::Right) as i32 - is_key_down(KeyCode::Left) as i32;
         for bullet in self.bullets.iter_mut() {
-            bullet.update(delta_time);
+            bullet.update(delta_time, turn as f32);
         }
+        self.blasts.extend(
+            self.bullets
+                .iter_mut()
+                .filter_map(|bullet| bullet.blast.take()),
+        );
         self.bullets.retain(|bullet| bullet.alive);
 
         self.settings.update();
@@ -310,26 +403,117 @@ impl Player {
         } else {
             self.speed = SPEED;
         }
+        self.speed *= self.item_effect().speed_scale() * self.statuses.speed_scale();
     }
 
-    pub fn update_equipment(&mut self) {
+    pub fn update_equipment(&mut self, delta_time: f32, gassed: bool) {
         self.equip_menu.update();
 
-        if self.equip_menu.left_selected > 0 {
-            if self.equip_menu.left_selected == (Item::Cigs as usize) {
-                self.effect.update(self.equip_menu.left_selected);
+        let item = self.equipped_item();
+        if item != self.worn {
+            self.worn.effect().unequip(self);
+            item.effect().equip(self);
+            self.worn = item;
+        }
 
-                let now = std::time::Instant::now();
-                let elapsed = now - self.last_effect_update;
-                if elapsed >= Duration::from_millis(1000) {
-                    if self.health > 1.0 {
-                        self.health -= 1.0;
-                        // eprintln!("Health: {:?}", self.health);
-                    }
-                    self.last_effect_update = now;
-                }
-            }
+        if self.is_smoking() {
+            self.cig_burnt += delta_time;
+        } else {
+            self.cig_burnt = 0.0;
         }
+
+        let effect = item.effect();
+        effect.update(self);
+
+        if gassed && !effect.filters_gas() {
+            self.statuses.apply(StatusKind::Gassed, HELD);
+        }
+
+        self.statuses.update(delta_time, &mut self.health);
+        if let Some(kind) = self.statuses.overlay() {
+            self.effect.update(kind);
+        }
+    }
+
+    pub fn equipped_item(&self) -> Item {
+        Item::from_index(self.equip_menu.left_selected).unwrap_or(Item::Empty)
+    }
+
+    pub fn item_effect(&self) -> &'static dyn ItemEffect {
+        self.equipped_item().effect()
+    }
+
+    pub fn is_smoking(&self) -> bool {
+        self.equipped_item() == Item::Cigs
+            && self
+                .equip_menu
+                .inventory
+                .count(Item::Cigs)
+                .is_some_and(|(count, _)| count > 0)
+    }
+
+    pub fn smoke(&mut self) {
+        self.statuses.apply(StatusKind::Smoking, HELD);
+
+        // Burning through the pack
+        if self.cig_burnt >= CIG_LIFE {
+            self.equip_menu.inventory.use_item(Item::Cigs);
+            self.cig_burnt = 0.0;
+        }
+    }
+
+    pub fn max_health(&self) -> f32 {
+        BASE_HEALTH + HEALTH_PER_RANK * self.equip_menu.inventory.rank() as f32
+    }
+
+    // Every hit on Snake comes through here so armor can soak it
+    pub fn take_damage(&mut self, amount: f32) {
+        let amount = amount * self.statuses.damage_scale();
+        self.health = (self.health - amount).max(0.0);
+    }
+
+    // Cigarette smoke covers Snake's scent
+    pub fn masks_scent(&self) -> bool {
+        self.is_smoking()
+    }
+
+    pub fn wears_goggles(&self) -> bool {
+        self.item_effect().sees_lasers()
+    }
+
+    pub fn visibility(&self) -> f32 {
+        self.item_effect().visibility(self.is_walking())
+    }
+
+    // Drops whatever Snake was doing
+    pub fn stand(&mut self) {
+        self.state = State::Standing;
+        self.punch_spent = true;
+        self.frame_counter = 0;
+    }
+
+    // Anything in the bag giving Snake away
+    pub fn is_transmitting(&self) -> bool {
+        Item::ALL
+            .iter()
+            .any(|item| self.equip_menu.inventory.owns_item(*item) && item.effect().transmits())
+    }
+
+    pub fn is_walking(&self) -> bool {
+        self.state == State::Walking || self.state == State::WalkingGun
+    }
+
+    // Area in front of the fists, live once per punch
+    pub fn punch_hitbox(&self) -> Option<Rect> {
+        if self.state != State::Punching || self.punch_spent {
+            return None;
+        }
+
+        Some(self.punch_area())
+    }
+
+    pub fn spend_punch(&mut self) {
+        self.punch_spent = true;
     }
 
     pub fn draw(&mut self) {
@@ -451,24 +635,48 @@ impl Player {
                 self.collider.h,
                 Color::new(0.0, 1.0, 0.0, 0.5),
             );
+
+            if self.state == State::Punching {
+                let hitbox = self.punch_area();
+                draw_rectangle(
+                    hitbox.x,
+                    hitbox.y,
+                    hitbox.w,
+                    hitbox.h,
+                    Color::new(1.0, 1.0, 0.0, 0.5),
+                );
+            }
         }
 
-        draw_texture_ex(
-            &self.texture,
-            self.bounds.x,
-            self.bounds.y,
-            WHITE,
-            DrawTextureParams {
-                source: Some(src_rect),
-                dest_size: Some(self.bounds.size()),
-                ..Default::default()
-            },
-        );
+        if self.item_effect().covers_snake() {
+            // Sat on the floor where his feet are
+            let size = FS_BOX * SCALE;
+            draw_texture_ex(
+                &self.box_texture,
+                self.bounds.center().x - size.x / 2.0,
+                self.bounds.bottom() - size.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(size),
+                    ..Default::default()
+                },
+            );
+        } else {
+            draw_texture_ex(
+                &self.texture,
+                self.bounds.x,
+                self.bounds.y,
+                WHITE,
+                DrawTextureParams {
+                    source: Some(src_rect),
+                    dest_size: Some(self.bounds.size()),
+                    ..Default::default()
+                },
+            );
+        }
 
-        if self.equip_menu.left_selected > 0 {
-            if self.equip_menu.left_selected == (Item::Cigs as usize) {
-                self.effect.draw(self.bounds, self.equip_menu.left_selected);
-            }
+        if let Some(kind) = self.statuses.overlay() {
+            self.effect.draw(self.bounds, kind);
         }
 
         // Bullets
@@ -501,12 +709,6 @@ impl Player {
     }
 
     async fn fire_weapon(&mut self) {
-        let size = match Weapon::from_index(self.equip_menu.right_selected) {
-            Some(Weapon::Empty) => Vec2::new(0.0, 0.0),
-            Some(Weapon::Handgun) => Vec2::new(64.0, 64.0),
-            None => todo!(),
-        };
-
         let position = match self.direction {
             Direction::Down => Vec2::new(self.bounds.center().x, self.bounds.center().y),
             Direction::Left => Vec2::new(self.collider.x, self.collider.y - 4.0),
@@ -514,20 +716,104 @@ impl Player {
             Direction::Right => Vec2::new(self.collider.x + self.collider.w, self.collider.y - 4.0),
         };
 
-        let direction = match self.direction {
-            Direction::Down => Vec2::new(0.0, 1.0),
-            Direction::Left => Vec2::new(-1.0, 0.0),
-            Direction::Up => Vec2::new(0.0, -1.0),
-            Direction::Right => Vec2::new(1.0, 0.0),
+        let direction = self.direction.vector();
+
+        let weapon = match Weapon::from_index(self.equip_menu.right_selected) {
+            Some(weapon) if self.can_act() => weapon,
+            _ => return,
+        };
+        let spec = weapon.spec();
+        let projectile = match spec.projectile {
+            Some(projectile) => projectile,
+            None => return,
+        };
+
+        let now = std::time::Instant::now();
+        if now - self.last_shot < spec.cooldown {
+            return;
+        }
+        if !self.equip_menu.inventory.use_ammo(weapon) {
+            return;
+        }
+        self.last_shot = now;
+
+        // Grenades are lobbed ahead, explosives are put down at Snake's feet
+        let (origin, target) = match projectile {
+            Projectile::Grenade => (position, position + direction * THROW_RANGE),
+            Projectile::Charge | Projectile::Mine => {
+                (self.collider.center(), self.collider.center())
+            }
+            _ => (position, position),
         };
 
-        if self.equip_menu.right_selected == (Weapon::Handgun as usize) {
-            eprintln!("Health: {:?}", self.health);
-            let bullet = Bullet::new(position, direction, size).await;
-            self.bullets.push(bullet);
+        let mut bullet = Bullet::new(projectile, origin, direction, target).await;
+        bullet.weapon = weapon;
+        self.bullets.push(bullet);
+
+        if let Some(noise) = spec.noise {
+            self.noises.push(Noise::new(noise, position));
+            self.sounds.push("gunshot");
+        } else if projectile == Projectile::Bullet {
+            self.sounds.push("silenced");
+        }
+    }
+
+    // Automatic weapons keep firing while the trigger is held
+    fn trigger_pulled(&self) -> bool {
+        let automatic = Weapon::from_index(self.equip_menu.right_selected)
+            .is_some_and(|weapon| weapon.spec().automatic);
+
+        if automatic {
+            is_key_down(KeyCode::F) || is_mouse_button_down(MouseButton::Left)
+        } else {
+            is_key_pressed(KeyCode::F) || is_mouse_button_pressed(MouseButton::Left)
         }
     }
 
+    fn punch_area(&self) -> Rect {
+        match self.direction {
+            Direction::Down => Rect::new(
+                self.collider.x,
+                self.collider.y + self.collider.h,
+                self.collider.w,
+                PUNCH_REACH,
+            ),
+            Direction::Left => Rect::new(
+                self.collider.x - PUNCH_REACH,
+                self.collider.y,
+                PUNCH_REACH,
+                self.collider.h,
+            ),
+            Direction::Up => Rect::new(
+                self.collider.x,
+                self.collider.y - PUNCH_REACH,
+                self.collider.w,
+                PUNCH_REACH,
+            ),
+            Direction::Right => Rect::new(
+                self.collider.x + self.collider.w,
+                self.collider.y,
+                PUNCH_REACH,
+                self.collider.h,
+            ),
+        }
+    }
+
+    // No room to swing inside a box, and no swinging while seeing stars
+    fn can_act(&self) -> bool {
+        !self.item_effect().covers_snake() && !self.statuses.has(StatusKind::Stunned)
+    }
+
+    fn start_punch(&mut self) {
+        if !self.can_act() {
+            return;
+        }
+
+        self.state = State::Punching;
+        self.punch_spent = false;
+        self.last_punch_update = std::time::Instant::now();
+    }
+
     async fn punch(&mut self) {
         // Update time vars
         let now = std::time::Instant::now();
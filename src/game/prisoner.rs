@@ -0,0 +1,136 @@
+use macroquad::prelude::*;
+use std::time::Duration;
+
+use crate::game::Settings;
+
+// A POW tied up in a room, freed when Snake gets to them.
+// Freeing one sets a flag by their name for hints to wait on.
+#[derive(Debug, Clone)]
+pub struct Prisoner {
+    settings: Settings,
+    texture: Texture2D,
+    look: u32,
+    frame_counter: u32,
+    frame_delay: Duration,
+    last_frame_update: std::time::Instant,
+    state: State,
+    pub name: String,
+    pub room: String,
+    pub bounds: Rect,
+    pub collider: Rect,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Captive,
+    Rescued,
+    Dead,
+}
+
+// Frame Size, prisoners.png is a row of looks two frames each
+const FS_PRISONER: Vec2 = Vec2::new(16.0, 33.0);
+const LOOKS: u32 = 8;
+
+// Max Frames
+const MF_PRISONER: u32 = 2;
+
+// Conts
+const SCALE: f32 = 2.0;
+const SHUTTER: u64 = 500;
+
+impl Prisoner {
+    pub async fn new(
+        settings: Settings,
+        name: &str,
+        look: u32,
+        position: Vec2,
+        room: &str,
+    ) -> Self {
+        // Load Textures
+        let texture = load_texture("assets/prisoners.png").await.unwrap();
+        texture.set_filter(FilterMode::Nearest);
+
+        let size = FS_PRISONER * SCALE;
+        let bounds = Rect::new(position.x, position.y, size.x, size.y);
+
+        Self {
+            settings,
+            texture,
+            look: look % LOOKS,
+            frame_counter: 0,
+            frame_delay: Duration::from_millis(SHUTTER),
+            last_frame_update: std::time::Instant::now(),
+            state: State::Captive,
+            name: name.to_string(),
+            room: room.to_string(),
+            bounds,
+            collider: Rect::new(bounds.x, bounds.y + size.y * 0.5, size.x, size.y * 0.5),
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.settings.update();
+
+        let now = std::time::Instant::now();
+        if now - self.last_frame_update >= self.frame_delay {
+            self.last_frame_update = now;
+            self.frame_counter = (self.frame_counter + 1) % MF_PRISONER;
+        }
+    }
+
+    pub fn draw(&self) {
+        if self.state != State::Captive {
+            return;
+        }
+
+        if self.settings.debug {
+            draw_rectangle(
+                self.collider.x,
+                self.collider.y,
+                self.collider.w,
+                self.collider.h,
+                Color::new(0.0, 1.0, 0.5, 0.5),
+            );
+        }
+
+        let frame = (self.look * MF_PRISONER + self.frame_counter) as f32;
+        draw_texture_ex(
+            &self.texture,
+            self.bounds.x,
+            self.bounds.y,
+            WHITE,
+            DrawTextureParams {
+                source: Some(Rect::new(
+                    FS_PRISONER.x * frame,
+                    0.0,
+                    FS_PRISONER.x,
+                    FS_PRISONER.y,
+                )),
+                dest_size: Some(self.bounds.size()),
+                ..Default::default()
+            },
+        );
+    }
+
+    pub fn is_captive(&self) -> bool {
+        self.state == State::Captive
+    }
+
+    pub fn rescue(&mut self) {
+        self.state = State::Rescued;
+    }
+
+    pub fn kill(&mut self) {
+        self.state = State::Dead;
+    }
+
+    // Flag set once they're out
+    pub fn rescued_flag(&self) -> String {
+        format!("rescued {}", self.name)
+    }
+
+    // Flag set once they're dead, so a load doesn't put them back
+    pub fn killed_flag(&self) -> String {
+        format!("killed {}", self.name)
+    }
+}
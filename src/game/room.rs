@@ -2,6 +2,7 @@ use macroquad::prelude::*;
 use std::path::Path;
 
 use crate::game::ColliderMap;
+use crate::game::Respawn;
 
 #[derive(Debug, Clone)]
 pub struct Room {
@@ -10,11 +11,19 @@ pub struct Room {
     pub position: Vec2,
     pub bounds: Rect,
     pub collider_map: ColliderMap,
+    pub noisy_floors: Vec<Rect>,
+    pub gas: bool,
+    pub dark: bool,
+    pub respawn: Respawn,
 }
 
 const MAP_SCALE: f32 = 2.0;
 const MAP_WIDTH: f32 = 512.0 * MAP_SCALE;
 const MAP_HEIGHT: f32 = 384.0 * MAP_SCALE;
+const GAS_ALPHA: f32 = 0.25;
+const GAS_PULSE: f32 = 0.05;
+const DARK_ALPHA: f32 = 0.92;
+const LIGHT_SIZE: f32 = 320.0;
 
 impl Room {
     pub async fn new(id: &str, pos_id: Vec2) -> Self {
@@ -38,15 +47,14 @@ impl Room {
         let bounds = Rect::new(position.x, position.y, MAP_WIDTH, MAP_HEIGHT);
 
         let collider_path = format!("assets/rooms/colliders/{:03}.png", id);
-        let collider_map: ColliderMap;
 
         let p = Path::new(&collider_path);
-        if p.exists() {
-            collider_map = ColliderMap::new(&collider_path, bounds).await;
+        let collider_map = if p.exists() {
+            ColliderMap::new(&collider_path, bounds).await
         } else {
             // eprintln!("File does not exist: {}", &collider_path);
-            collider_map = ColliderMap::new("assets/rooms/colliders/xxx.png", bounds).await;
-        }
+            ColliderMap::new("assets/rooms/colliders/xxx.png", bounds).await
+        };
 
         Room {
             name: id.to_string(),
@@ -54,9 +62,21 @@ impl Room {
             position,
             bounds,
             collider_map,
+            noisy_floors: Vec::new(),
+            gas: false,
+            dark: false,
+            respawn: Respawn::Never,
         }
     }
 
+    // Which slot of the floor grid a point falls in
+    pub fn cell_at(point: Vec2) -> (i32, i32) {
+        (
+            (point.x / MAP_WIDTH).floor() as i32,
+            (point.y / MAP_HEIGHT).floor() as i32,
+        )
+    }
+
     pub fn draw(&self) {
         draw_texture_ex(
             &self.texture,
@@ -68,12 +88,66 @@ impl Room {
                 ..Default::default()
             },
         );
+
+        if self.gas {
+            // Slow yellow-green haze
+            let alpha = GAS_ALPHA + GAS_PULSE * (get_time() as f32 * 2.0).sin();
+            draw_rectangle(
+                self.bounds.x,
+                self.bounds.y,
+                self.bounds.w,
+                self.bounds.h,
+                Color::new(0.6, 0.8, 0.1, alpha),
+            );
+        }
+    }
+
+    // Drawn over everything in the room, a light leaves a patch around it
+    pub fn draw_darkness(&self, light: Option<Vec2>) {
+        if !self.dark {
+            return;
+        }
+
+        let shade = Color::new(0.0, 0.0, 0.0, DARK_ALPHA);
+        let bounds = self.bounds;
+        let light = match light {
+            Some(center) => Rect::new(
+                center.x - LIGHT_SIZE / 2.0,
+                center.y - LIGHT_SIZE / 2.0,
+                LIGHT_SIZE,
+                LIGHT_SIZE,
+            ),
+            None => {
+                draw_rectangle(bounds.x, bounds.y, bounds.w, bounds.h, shade);
+                return;
+            }
+        };
+
+        // Above, below, then either side of the light
+        let top = light.y.clamp(bounds.y, bounds.bottom());
+        let bottom = light.bottom().clamp(bounds.y, bounds.bottom());
+        let left = light.x.clamp(bounds.x, bounds.right());
+        let right = light.right().clamp(bounds.x, bounds.right());
+        draw_rectangle(bounds.x, bounds.y, bounds.w, top - bounds.y, shade);
+        draw_rectangle(bounds.x, bottom, bounds.w, bounds.bottom() - bottom, shade);
+        draw_rectangle(bounds.x, top, left - bounds.x, bottom - top, shade);
+        draw_rectangle(right, top, bounds.right() - right, bottom - top, shade);
     }
 
     pub fn draw_debug(&self) {
         // Debug info
         self.collider_map.draw();
 
+        for floor in &self.noisy_floors {
+            draw_rectangle(
+                floor.x,
+                floor.y,
+                floor.w,
+                floor.h,
+                Color::new(0.0, 0.5, 1.0, 0.3),
+            );
+        }
+
         draw_text(
             &self.name,
             self.position.x + 16.0,
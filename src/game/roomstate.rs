@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+
+// What's changed in each room, kept by room id rather than on the Room,
+// which gets cloned into the game on every walk through a door. Entries go
+// by spawn id, the room and where it was placed.
+#[derive(Debug, Clone, Default)]
+pub struct RoomStates {
+    rooms: HashMap<String, RoomState>,
+    // Seconds played, for timed respawns
+    clock: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RoomState {
+    killed: HashSet<String>,
+    taken: HashSet<String>,
+    left_at: f32,
+}
+
+// When a room's guards and supplies come back
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Respawn {
+    Never,
+    // Every time Snake walks back in
+    Enter,
+    // Once he's been away this many seconds
+    After(f32),
+}
+
+impl Respawn {
+    // Spawn args: never | enter | seconds
+    pub fn from_args(args: &[String]) -> Self {
+        match args.first().map(String::as_str) {
+            Some("enter") => Respawn::Enter,
+            Some("never") | None => Respawn::Never,
+            Some(arg) => match arg.parse::<f32>() {
+                Ok(seconds) => Respawn::After(seconds),
+                Err(_) => {
+                    eprintln!("Bad respawn rule: {}", arg);
+                    Respawn::Never
+                }
+            },
+        }
+    }
+}
+
+impl RoomStates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.clock += delta_time;
+    }
+
+    // True when the rule brings the room's dead back, they're forgotten here
+    // and it's up to the game to spawn them again
+    pub fn enter(&mut self, left: &str, entered: &str, rule: Respawn) -> bool {
+        let clock = self.clock;
+        self.room(left).left_at = clock;
+
+        let state = match self.rooms.get_mut(entered) {
+            Some(state) => state,
+            None => return false,
+        };
+        let back = match rule {
+            Respawn::Never => false,
+            Respawn::Enter => true,
+            Respawn::After(seconds) => clock - state.left_at >= seconds,
+        };
+        if back {
+            state.killed.clear();
+        }
+        back
+    }
+
+    pub fn kill(&mut self, room: &str, id: &str) {
+        self.room(room).killed.insert(id.to_string());
+    }
+
+    pub fn is_killed(&self, room: &str, id: &str) -> bool {
+        self.rooms
+            .get(room)
+            .is_some_and(|state| state.killed.contains(id))
+    }
+
+    pub fn take(&mut self, room: &str, id: &str) {
+        self.room(room).taken.insert(id.to_string());
+    }
+
+    pub fn is_taken(&self, room: &str, id: &str) -> bool {
+        self.rooms
+            .get(room)
+            .is_some_and(|state| state.taken.contains(id))
+    }
+
+    // Puts a pickup back, true if it had been taken
+    pub fn give_back(&mut self, room: &str, id: &str) -> bool {
+        self.rooms
+            .get_mut(room)
+            .is_some_and(|state| state.taken.remove(id))
+    }
+
+    // Room and id pairs, sorted so the same game saves the same file
+    pub fn killed(&self) -> Vec<(String, String)> {
+        self.entries(|state| &state.killed)
+    }
+
+    pub fn taken(&self) -> Vec<(String, String)> {
+        self.entries(|state| &state.taken)
+    }
+
+    pub fn clock(&self) -> f32 {
+        self.clock
+    }
+
+    // Room and when Snake last left it, sorted like the rest
+    pub fn left_at(&self) -> Vec<(String, f32)> {
+        let mut left_at: Vec<(String, f32)> = self
+            .rooms
+            .iter()
+            .map(|(room, state)| (room.clone(), state.left_at))
+            .collect();
+        left_at.sort_by(|a, b| a.0.cmp(&b.0));
+        left_at
+    }
+
+    pub fn restore(
+        &mut self,
+        killed: &[(String, String)],
+        taken: &[(String, String)],
+        clock: f32,
+        left_at: &[(String, f32)],
+    ) {
+        self.rooms.clear();
+        self.clock = clock;
+        for (room, at) in left_at {
+            self.room(room).left_at = *at;
+        }
+        for (room, id) in killed {
+            self.kill(room, id);
+        }
+        for (room, id) in taken {
+            self.take(room, id);
+        }
+    }
+
+    // Private
+    fn room(&mut self, name: &str) -> &mut RoomState {
+        self.rooms.entry(name.to_string()).or_default()
+    }
+
+    fn entries(&self, set: impl Fn(&RoomState) -> &HashSet<String>) -> Vec<(String, String)> {
+        let mut entries: Vec<(String, String)> = self
+            .rooms
+            .iter()
+            .flat_map(|(room, state)| set(state).iter().map(|id| (room.clone(), id.clone())))
+            .collect();
+        entries.sort();
+        entries
+    }
+}
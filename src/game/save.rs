@@ -0,0 +1,440 @@
+use macroquad::prelude::*;
+
+use std::fs;
+use std::io;
+
+use super::equipmenu::{Item, Weapon};
+
+// Save slots live in saves/, one file each, as key: value lines under a
+// version line. Older versions still load, missing keys keep their defaults.
+// Mines aren't kept: Snake's burn out within the minute and the floor's are
+// laid again with it.
+#[derive(Debug, Clone)]
+pub struct SaveData {
+    pub floor: String,
+    pub room: String,
+    pub position: Vec2,
+    pub health: f32,
+    pub rank: u32,
+    pub rescues: u32,
+    // Owned, with the rounds or count held
+    pub weapons: Vec<(Weapon, u32)>,
+    pub items: Vec<(Item, u32)>,
+    pub left: Item,
+    pub right: Weapon,
+    pub flags: Vec<String>,
+    // Room and spawn id
+    pub killed: Vec<(String, String)>,
+    pub taken: Vec<(String, String)>,
+    // The respawn clock, and when Snake last left each room by it
+    pub clock: f32,
+    pub left_at: Vec<(String, f32)>,
+    pub locked: Vec<String>,
+    pub scripts: Vec<String>,
+    pub calls: Vec<usize>,
+}
+
+// What a password holds, the rest starts over
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Password {
+    pub rank: u32,
+    // One bit each, by menu order and by spawn order on the floor
+    pub weapons: u32,
+    pub items: u32,
+    pub bosses: u32,
+    // Rescued, and killed
+    pub prisoners: u32,
+    pub killed: u32,
+}
+
+// 3 adds the respawn clock, 2 keeps what's gone by room, 1 only had the
+// pickups as collected
+pub const VERSION: u32 = 3;
+pub const SLOTS: usize = 3;
+const SAVE_DIR: &str = "saves";
+
+// Password bits, widest first. 3 adds killed prisoners, 2 checks with a CRC,
+// 1's sum let typos through.
+const PASSWORD_VERSION: u64 = 3;
+const RANK_BITS: u32 = 3;
+const WEAPON_BITS: u32 = 8;
+const ITEM_BITS: u32 = 15;
+const BOSS_BITS: u32 = 4;
+const PRISONER_BITS: u32 = 8;
+const CHECK_BITS: u32 = 8;
+// CRC-8, catches any run of up to eight wrong bits, so any one wrong letter
+const CHECK_POLY: u64 = 0x07;
+// No 0/O or 1/I to mix up
+const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+pub const PASSWORD_LENGTH: usize = 12;
+// A floor with more than this can't be written down
+pub const MAX_BOSSES: usize = BOSS_BITS as usize;
+pub const MAX_PRISONERS: usize = PRISONER_BITS as usize;
+
+impl SaveData {
+    pub fn slot_path(slot: usize) -> String {
+        format!("{}/slot{}.txt", SAVE_DIR, slot + 1)
+    }
+
+    // The last checkpoint, overwritten on every new room
+    pub fn checkpoint_path() -> String {
+        format!("{}/checkpoint.txt", SAVE_DIR)
+    }
+
+    pub fn write(&self, path: &str) -> io::Result<()> {
+        let mut lines = vec![
+            format!("version: {}", VERSION),
+            format!("floor: {}", self.floor),
+            format!("room: {}", self.room),
+            format!("position: {}, {}", self.position.x, self.position.y),
+            format!("health: {}", self.health),
+            format!("rank: {}, {}", self.rank, self.rescues),
+            format!("left: {}", self.left.name()),
+            format!("right: {}", self.right.name()),
+            format!("clock: {}", self.clock),
+        ];
+        for (weapon, ammo) in &self.weapons {
+            lines.push(format!("weapon: {}, {}", weapon.name(), ammo));
+        }
+        for (item, count) in &self.items {
+            lines.push(format!("item: {}, {}", item.name(), count));
+        }
+        let lists = [
+            ("flag", &self.flags),
+            ("locked", &self.locked),
+            ("script", &self.scripts),
+        ];
+        for (key, values) in lists {
+            for value in values {
+                lines.push(format!("{}: {}", key, value));
+            }
+        }
+        for (key, entries) in [("killed", &self.killed), ("taken", &self.taken)] {
+            for (room, id) in entries {
+                lines.push(format!("{}: {}; {}", key, room, id));
+            }
+        }
+        for (room, left_at) in &self.left_at {
+            lines.push(format!("left at: {}; {}", room, left_at));
+        }
+        for call in &self.calls {
+            lines.push(format!("call: {}", call));
+        }
+
+        fs::create_dir_all(SAVE_DIR)?;
+        fs::write(path, lines.join("\n") + "\n")
+    }
+
+    pub fn read(path: &str) -> Option<Self> {
+        let source = fs::read_to_string(path).ok()?;
+
+        let mut save = SaveData {
+            floor: String::new(),
+            room: String::new(),
+            position: Vec2::ZERO,
+            health: 0.0,
+            rank: 0,
+            rescues: 0,
+            weapons: Vec::new(),
+            items: Vec::new(),
+            left: Item::Empty,
+            right: Weapon::Empty,
+            flags: Vec::new(),
+            killed: Vec::new(),
+            taken: Vec::new(),
+            clock: 0.0,
+            left_at: Vec::new(),
+            locked: Vec::new(),
+            scripts: Vec::new(),
+            calls: Vec::new(),
+        };
+        let mut version = None;
+
+        for line in source.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => {
+                    eprintln!("Bad save line: {}", line);
+                    continue;
+                }
+            };
+            // Names can have spaces but never commas
+            let (name, number) = match value.rsplit_once(',') {
+                Some((name, number)) => (name.trim(), number.trim().parse::<f32>().ok()),
+                None => (value, None),
+            };
+
+            match key {
+                "version" => version = value.parse::<u32>().ok(),
+                "floor" => save.floor = value.to_string(),
+                "room" => save.room = value.to_string(),
+                "position" => {
+                    if let (Ok(x), Some(y)) = (name.parse::<f32>(), number) {
+                        save.position = vec2(x, y);
+                    }
+                }
+                "health" => save.health = value.parse::<f32>().unwrap_or(0.0),
+                "rank" => {
+                    save.rank = name.parse::<u32>().unwrap_or(0);
+                    save.rescues = number.unwrap_or(0.0) as u32;
+                }
+                "left" => save.left = Item::from_name(value).unwrap_or(Item::Empty),
+                "right" => save.right = Weapon::from_name(value).unwrap_or(Weapon::Empty),
+                "weapon" => match Weapon::from_name(name) {
+                    Some(weapon) => save.weapons.push((weapon, number.unwrap_or(0.0) as u32)),
+                    None => eprintln!("Unknown saved weapon: {}", name),
+                },
+                "item" => match Item::from_name(name) {
+                    Some(item) => save.items.push((item, number.unwrap_or(0.0) as u32)),
+                    None => eprintln!("Unknown saved item: {}", name),
+                },
+                "flag" => save.flags.push(value.to_string()),
+                "killed" | "taken" => match value.split_once(';') {
+                    Some((room, id)) => {
+                        let entry = (room.trim().to_string(), id.trim().to_string());
+                        match key {
+                            "killed" => save.killed.push(entry),
+                            _ => save.taken.push(entry),
+                        }
+                    }
+                    None => eprintln!("Bad save line: {}", line),
+                },
+                "clock" => save.clock = value.parse::<f32>().unwrap_or(0.0),
+                "left at" => match value
+                    .split_once(';')
+                    .map(|(room, left_at)| (room.trim(), left_at.trim().parse::<f32>()))
+                {
+                    Some((room, Ok(left_at))) => save.left_at.push((room.to_string(), left_at)),
+                    _ => eprintln!("Bad save line: {}", line),
+                },
+                // Ids start with their room
+                "collected" => match value.split_once(':') {
+                    Some((room, _)) => save.taken.push((room.to_string(), value.to_string())),
+                    None => eprintln!("Bad save line: {}", line),
+                },
+                "locked" => save.locked.push(value.to_string()),
+                "script" => save.scripts.push(value.to_string()),
+                "call" => save.calls.extend(value.parse::<usize>().ok()),
+                _ => eprintln!("Bad save line: {}", line),
+            }
+        }
+
+        match version {
+            Some(version) if version <= VERSION => {}
+            Some(version) => {
+                eprintln!("Save is from a newer version ({}): {}", version, path);
+                return None;
+            }
+            None => {
+                eprintln!("Save without a version: {}", path);
+                return None;
+            }
+        }
+        if save.floor.is_empty() {
+            eprintln!("Save without a floor: {}", path);
+            return None;
+        }
+
+        Some(save)
+    }
+
+    // For the slot list
+    pub fn describe(&self) -> String {
+        format!("{} room {}, rank {}", self.floor, self.room, self.rank)
+    }
+}
+
+impl Password {
+    // Twelve letters, split in two to read out
+    pub fn encode(&self) -> String {
+        let mut bits = PASSWORD_VERSION;
+        let fields = [
+            (self.rank as u64, RANK_BITS),
+            (self.weapons as u64, WEAPON_BITS),
+            (self.items as u64, ITEM_BITS),
+            (self.bosses as u64, BOSS_BITS),
+            (self.prisoners as u64, PRISONER_BITS),
+            (self.killed as u64, PRISONER_BITS),
+        ];
+        for (value, width) in fields {
+            bits = (bits << width) | (value & mask(width));
+        }
+        bits = (bits << CHECK_BITS) | checksum(bits);
+
+        let letters: String = (0..PASSWORD_LENGTH)
+            .rev()
+            .map(|index| ALPHABET[((bits >> (index * 5)) & 31) as usize] as char)
+            .collect();
+        let half = PASSWORD_LENGTH / 2;
+        format!("{}-{}", &letters[..half], &letters[half..])
+    }
+
+    // Anything mistyped fails the check
+    pub fn decode(text: &str) -> Option<Self> {
+        let letters: Vec<u8> = text
+            .bytes()
+            .filter(|byte| *byte != b'-' && *byte != b' ')
+            .map(|byte| byte.to_ascii_uppercase())
+            .collect();
+        if letters.len() != PASSWORD_LENGTH {
+            return None;
+        }
+
+        let mut bits: u64 = 0;
+        for letter in letters {
+            let value = ALPHABET.iter().position(|known| *known == letter)?;
+            bits = (bits << 5) | value as u64;
+        }
+
+        let check = bits & mask(CHECK_BITS);
+        bits >>= CHECK_BITS;
+        if checksum(bits) != check {
+            return None;
+        }
+
+        let mut take = |width: u32| {
+            let value = bits & mask(width);
+            bits >>= width;
+            value as u32
+        };
+        let killed = take(PRISONER_BITS);
+        let prisoners = take(PRISONER_BITS);
+        let bosses = take(BOSS_BITS);
+        let items = take(ITEM_BITS);
+        let weapons = take(WEAPON_BITS);
+        let rank = take(RANK_BITS);
+        if bits != PASSWORD_VERSION {
+            return None;
+        }
+
+        Some(Self {
+            rank,
+            weapons,
+            items,
+            bosses,
+            prisoners,
+            killed,
+        })
+    }
+}
+
+fn mask(width: u32) -> u64 {
+    (1 << width) - 1
+}
+
+// Over every bit the letters hold ahead of the check, leading zeros too
+fn checksum(bits: u64) -> u64 {
+    let width = PASSWORD_LENGTH as u32 * 5 - CHECK_BITS;
+    let mut crc: u64 = 0;
+    for index in (0..width).rev() {
+        let feedback = (crc >> (CHECK_BITS - 1) ^ bits >> index) & 1;
+        crc = (crc << 1) & mask(CHECK_BITS);
+        if feedback == 1 {
+            crc ^= CHECK_POLY;
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn password(rank: u32, weapons: u32, items: u32, bosses: u32, prisoners: u32) -> Password {
+        Password {
+            rank,
+            weapons,
+            items,
+            bosses,
+            prisoners,
+            killed: !prisoners & mask(PRISONER_BITS) as u32,
+        }
+    }
+
+    #[test]
+    fn passwords_decode_to_what_was_encoded() {
+        let passwords = [
+            password(0, 0, 0, 0, 0),
+            password(2, 0b101, 0b1000_0000_0011, 0b01, 0b1100),
+            password(
+                mask(RANK_BITS) as u32,
+                mask(WEAPON_BITS) as u32,
+                mask(ITEM_BITS) as u32,
+                mask(BOSS_BITS) as u32,
+                mask(PRISONER_BITS) as u32,
+            ),
+        ];
+
+        for password in passwords {
+            let text = password.encode();
+            assert_eq!(text.len(), PASSWORD_LENGTH + 1);
+            assert_eq!(Password::decode(&text), Some(password));
+            // Read back without the dash, in any case
+            let typed = text.replace('-', " ").to_lowercase();
+            assert_eq!(Password::decode(&typed), Some(password));
+        }
+    }
+
+    #[test]
+    fn any_one_wrong_letter_fails_the_check() {
+        let text = password(3, 0b11, 0b110, 0b1, 0b101).encode();
+
+        for (index, letter) in text
+            .bytes()
+            .enumerate()
+            .filter(|(_, letter)| *letter != b'-')
+        {
+            for &wrong in ALPHABET.iter().filter(|&&wrong| wrong != letter) {
+                let mut typo = text.clone().into_bytes();
+                typo[index] = wrong;
+                let typo = String::from_utf8(typo).unwrap();
+                assert_eq!(Password::decode(&typo), None, "{} passed", typo);
+            }
+        }
+    }
+
+    #[test]
+    fn passwords_of_the_wrong_length_or_letters_fail() {
+        assert_eq!(Password::decode("ABCDEF-GHJKL"), None);
+        assert_eq!(Password::decode("ABCDEF-GHJKLMN"), None);
+        assert_eq!(Password::decode("ABCDEF-GHJKL0"), None);
+    }
+
+    #[test]
+    fn the_respawn_clock_survives_a_save() {
+        let path = std::env::temp_dir().join(format!("save_clock_{}.txt", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let source = "version: 3\nfloor: b1_f1\nroom: 004\nclock: 95.5\nleft at: 004; 40.25\n";
+        fs::write(&path, source).unwrap();
+
+        let save = SaveData::read(&path);
+        fs::remove_file(&path).unwrap();
+
+        let save = save.expect("version 3 saves load");
+        assert_eq!(save.clock, 95.5);
+        assert_eq!(save.left_at, [("004".to_string(), 40.25)]);
+    }
+
+    #[test]
+    fn version_one_pickups_are_taken_in_their_room() {
+        let path = std::env::temp_dir().join(format!("save_v1_{}.txt", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let source = "version: 1\nfloor: b1_f1\nroom: 004\ncollected: 004:1024:512\n";
+        fs::write(&path, source).unwrap();
+
+        let save = SaveData::read(&path);
+        fs::remove_file(&path).unwrap();
+
+        let save = save.expect("version 1 saves still load");
+        assert_eq!(save.room, "004");
+        assert_eq!(
+            save.taken,
+            [("004".to_string(), "004:1024:512".to_string())]
+        );
+    }
+}
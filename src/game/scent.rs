@@ -0,0 +1,107 @@
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+use crate::game::Settings;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Crumb {
+    pub id: u64,
+    pub position: Vec2,
+    age: f32,
+}
+
+// Breadcrumbs dropped behind Snake, oldest first
+#[derive(Debug, Clone)]
+pub struct ScentTrail {
+    settings: Settings,
+    crumbs: VecDeque<Crumb>,
+    next_id: u64,
+}
+
+const SPACING: f32 = 48.0;
+const LIFE: f32 = 12.0;
+const MAX_CRUMBS: usize = 128;
+
+impl ScentTrail {
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            settings,
+            crumbs: VecDeque::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32, position: Vec2, masked: bool) {
+        self.settings.update();
+
+        for crumb in self.crumbs.iter_mut() {
+            crumb.age += delta_time;
+        }
+        while self.crumbs.front().is_some_and(|crumb| crumb.age >= LIFE) {
+            self.crumbs.pop_front();
+        }
+
+        // Masked scent leaves nothing to follow
+        if masked {
+            self.crumbs.clear();
+            return;
+        }
+
+        let moved = match self.crumbs.back() {
+            Some(last) => last.position.distance(position) >= SPACING,
+            None => true,
+        };
+        if moved {
+            self.crumbs.push_back(Crumb {
+                id: self.next_id,
+                position,
+                age: 0.0,
+            });
+            self.next_id += 1;
+
+            if self.crumbs.len() > MAX_CRUMBS {
+                self.crumbs.pop_front();
+            }
+        }
+    }
+
+    // Nearest crumb within range of the nose
+    pub fn sniff(&self, point: Vec2, range: f32) -> Option<Crumb> {
+        self.crumbs
+            .iter()
+            .filter(|crumb| crumb.position.distance(point) <= range)
+            .min_by(|a, b| {
+                a.position
+                    .distance(point)
+                    .partial_cmp(&b.position.distance(point))
+                    .unwrap()
+            })
+            .copied()
+    }
+
+    // The next crumb laid after the given one
+    pub fn after(&self, id: u64) -> Option<Crumb> {
+        self.crumbs.iter().find(|crumb| crumb.id > id).copied()
+    }
+
+    // Whether anything from this crumb onwards is still on the ground
+    pub fn still_fresh(&self, id: u64) -> bool {
+        self.crumbs.iter().any(|crumb| crumb.id >= id)
+    }
+
+    pub fn draw(&self) {
+        if !self.settings.debug {
+            return;
+        }
+
+        for crumb in &self.crumbs {
+            let fade = 1.0 - crumb.age / LIFE;
+            draw_circle(
+                crumb.position.x,
+                crumb.position.y,
+                4.0,
+                Color::new(0.6, 0.4, 0.2, fade),
+            );
+        }
+    }
+}
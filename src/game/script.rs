@@ -0,0 +1,550 @@
+use macroquad::prelude::*;
+
+use std::collections::HashSet;
+use std::fs;
+use std::time::SystemTime;
+
+use crate::game::{PickupKind, Spawn};
+
+// Script files live in assets/scripts, one per floor. A script starts with
+// what sets it off, runs its commands in order and stops at end:
+// on enter <room> | on flag <name> | on rescue <name> | on defeat <boss>[, once]
+// Rescues and defeats are the flags the game raises for them.
+// Scripts never touch the game, they leave actions for it to carry out and
+// only see the room and the story flags, so they run without a window too.
+#[derive(Debug, Clone)]
+pub struct Scripts {
+    path: String,
+    modified: Option<SystemTime>,
+    scripts: Vec<Script>,
+    running: Vec<Run>,
+    // Once scripts that have gone, by their on line
+    fired: HashSet<String>,
+    // Trigger flags that were already up last frame
+    raised: HashSet<String>,
+    room: String,
+    reload_timer: f32,
+    pub actions: Vec<Action>,
+}
+
+// For the game to carry out
+#[derive(Debug, Clone)]
+pub enum Action {
+    // Speaker and text, said over the codec
+    Say(Vec<(String, String)>),
+    Notify(String),
+    Give(PickupKind),
+    Spawn(Spawn),
+    Lock(String),
+    Unlock(String),
+    // Looks at a cell in a room for a while
+    Camera { room: String, cell: Vec2, time: f32 },
+    Sound(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Trigger {
+    Enter(String),
+    Flag(String),
+}
+
+#[derive(Debug, Clone)]
+struct Script {
+    header: String,
+    trigger: Trigger,
+    once: bool,
+    commands: Vec<Command>,
+}
+
+#[derive(Debug, Clone)]
+enum Command {
+    Say { speaker: String, text: String },
+    Set(String),
+    Clear(String),
+    // Jumps past its end unless the flag is set or not, as wanted
+    If { flag: String, set: bool, end: usize },
+    Wait(f32),
+    Do(Action),
+}
+
+#[derive(Debug, Clone)]
+struct Run {
+    script: usize,
+    next: usize,
+    wait: Wait,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Wait {
+    None,
+    Time(f32),
+    // Until the codec's closed again
+    Dialog,
+}
+
+// How often the file is checked for changes
+const RELOAD_INTERVAL: f32 = 1.0;
+
+impl Scripts {
+    pub fn new(script_file: &str) -> Self {
+        Self {
+            path: script_file.to_string(),
+            modified: modified(script_file),
+            ..Self::from_source(&read(script_file))
+        }
+    }
+
+    // Scripts from text rather than a file, never reloaded
+    pub fn from_source(source: &str) -> Self {
+        let mut scripts = Self {
+            path: String::new(),
+            modified: None,
+            scripts: Vec::new(),
+            running: Vec::new(),
+            fired: HashSet::new(),
+            raised: HashSet::new(),
+            room: String::new(),
+            reload_timer: 0.0,
+            actions: Vec::new(),
+        };
+        scripts.parse(source);
+
+        scripts
+    }
+
+    // Once scripts that have gone, for saves
+    pub fn fired(&self) -> Vec<String> {
+        let mut fired: Vec<String> = self.fired.iter().cloned().collect();
+        fired.sort();
+        fired
+    }
+
+    // Flags that were up in a loaded game don't set anything off again
+    pub fn restore(&mut self, fired: &[String], flags: &HashSet<String>) {
+        self.fired = fired.iter().cloned().collect();
+        self.raised = flags.clone();
+    }
+
+    // Busy is whether the codec is still up from a say
+    pub fn update(&mut self, delta_time: f32, room: &str, flags: &mut HashSet<String>, busy: bool) {
+        // Edits to the file show up without a restart
+        self.reload_timer += delta_time;
+        if self.reload_timer >= RELOAD_INTERVAL {
+            self.reload_timer = 0.0;
+            if self.modified != modified(&self.path) {
+                self.load();
+                eprintln!("Reloaded scripts: {}", self.path);
+            }
+        }
+
+        let entered = room != self.room;
+        if entered {
+            self.room = room.to_string();
+        }
+
+        // Flags only set things off the moment they go up
+        let mut raised = HashSet::new();
+        for index in 0..self.scripts.len() {
+            let start = match &self.scripts[index].trigger {
+                Trigger::Enter(name) => entered && name == room,
+                Trigger::Flag(flag) if flags.contains(flag) => {
+                    raised.insert(flag.clone());
+                    !self.raised.contains(flag)
+                }
+                Trigger::Flag(_) => false,
+            };
+            if start {
+                self.start(index);
+            }
+        }
+        self.raised = raised;
+
+        let mut running = std::mem::take(&mut self.running);
+        running.retain_mut(|run| {
+            let script = &self.scripts[run.script];
+            !step(script, run, delta_time, flags, busy, &mut self.actions)
+        });
+        self.running = running;
+    }
+
+    // Private
+    fn start(&mut self, index: usize) {
+        let script = &self.scripts[index];
+        if script.once && self.fired.contains(&script.header) {
+            return;
+        }
+        if self.running.iter().any(|run| run.script == index) {
+            return;
+        }
+
+        if script.once {
+            self.fired.insert(script.header.clone());
+        }
+        self.running.push(Run {
+            script: index,
+            next: 0,
+            wait: Wait::None,
+        });
+    }
+
+    fn load(&mut self) {
+        self.modified = modified(&self.path);
+        let source = read(&self.path);
+        self.parse(&source);
+    }
+
+    // Anything half run is dropped, the lines may have moved
+    fn parse(&mut self, source: &str) {
+        self.scripts.clear();
+        self.running.clear();
+
+        let mut script: Option<Script> = None;
+        // Open ifs, by command index
+        let mut blocks: Vec<usize> = Vec::new();
+
+        for (number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (word, rest) = match line.split_once(' ') {
+                Some((word, rest)) => (word, rest.trim()),
+                None => (line, ""),
+            };
+
+            let current = match script.as_mut() {
+                Some(current) => current,
+                None => {
+                    match (word, Script::parse_header(line, rest)) {
+                        ("on", Some(header)) => script = Some(header),
+                        _ => eprintln!("Script line {} outside a script: {}", number + 1, line),
+                    }
+                    continue;
+                }
+            };
+
+            if word == "end" {
+                match blocks.pop() {
+                    Some(index) => {
+                        let end = current.commands.len();
+                        if let Command::If { end: jump, .. } = &mut current.commands[index] {
+                            *jump = end;
+                        }
+                    }
+                    None => self.scripts.extend(script.take()),
+                }
+                continue;
+            }
+
+            let command = match word {
+                "say" => rest.split_once(';').map(|(speaker, text)| Command::Say {
+                    speaker: speaker.trim().to_string(),
+                    text: text.trim().to_string(),
+                }),
+                "set" if !rest.is_empty() => Some(Command::Set(rest.to_string())),
+                "clear" if !rest.is_empty() => Some(Command::Clear(rest.to_string())),
+                "if" => {
+                    blocks.push(current.commands.len());
+                    let (flag, set) = match rest.strip_prefix("not ") {
+                        Some(flag) => (flag.trim(), false),
+                        None => (rest, true),
+                    };
+                    Some(Command::If {
+                        flag: flag.to_string(),
+                        set,
+                        end: 0,
+                    })
+                }
+                "wait" => rest.parse::<f32>().ok().map(Command::Wait),
+                "notify" => Some(Command::Do(Action::Notify(rest.to_string()))),
+                "give" => {
+                    let args: Vec<String> = rest.split(',').map(|s| s.trim().to_owned()).collect();
+                    PickupKind::from_args(&args).map(|kind| Command::Do(Action::Give(kind)))
+                }
+                "spawn" => Spawn::parse(rest).map(|spawn| Command::Do(Action::Spawn(spawn))),
+                "lock" if !rest.is_empty() => Some(Command::Do(Action::Lock(rest.to_string()))),
+                "unlock" if !rest.is_empty() => Some(Command::Do(Action::Unlock(rest.to_string()))),
+                // camera room, cell x, cell y, seconds
+                "camera" => {
+                    let fields: Vec<&str> = rest.split(',').map(|s| s.trim()).collect();
+                    let numbers: Vec<f32> = fields
+                        .iter()
+                        .skip(1)
+                        .filter_map(|s| s.parse::<f32>().ok())
+                        .collect();
+                    (numbers.len() == 3).then(|| {
+                        Command::Do(Action::Camera {
+                            room: fields[0].to_string(),
+                            cell: vec2(numbers[0], numbers[1]),
+                            time: numbers[2],
+                        })
+                    })
+                }
+                "sound" if !rest.is_empty() => Some(Command::Do(Action::Sound(rest.to_string()))),
+                _ => None,
+            };
+
+            match command {
+                Some(command) => current.commands.push(command),
+                None => eprintln!("Bad script line {}: {}", number + 1, line),
+            }
+        }
+
+        if let Some(script) = script {
+            eprintln!("Script without an end: {}", script.header);
+        }
+    }
+}
+
+impl Script {
+    // on enter <room> | on flag <name> | on rescue <name> | on defeat <boss>[, once]
+    fn parse_header(line: &str, rest: &str) -> Option<Self> {
+        let (trigger, once) = match rest.rsplit_once(',') {
+            Some((trigger, once)) if once.trim() == "once" => (trigger.trim(), true),
+            _ => (rest, false),
+        };
+
+        let trigger = match trigger.split_once(' ')? {
+            ("enter", room) => Trigger::Enter(room.trim().to_string()),
+            ("flag", flag) => Trigger::Flag(flag.trim().to_string()),
+            ("rescue", name) => Trigger::Flag(format!("rescued {}", name.trim())),
+            ("defeat", boss) => Trigger::Flag(format!("defeated {}", boss.trim().to_lowercase())),
+            _ => return None,
+        };
+
+        Some(Script {
+            header: line.to_string(),
+            trigger,
+            once,
+            commands: Vec::new(),
+        })
+    }
+}
+
+// Runs a script on until it has to wait, true once it's done
+fn step(
+    script: &Script,
+    run: &mut Run,
+    delta_time: f32,
+    flags: &mut HashSet<String>,
+    busy: bool,
+    actions: &mut Vec<Action>,
+) -> bool {
+    match &mut run.wait {
+        Wait::Time(left) => {
+            *left -= delta_time;
+            if *left > 0.0 {
+                return false;
+            }
+        }
+        Wait::Dialog if busy => return false,
+        _ => {}
+    }
+    run.wait = Wait::None;
+
+    while let Some(command) = script.commands.get(run.next) {
+        run.next += 1;
+
+        match command {
+            // Says in a row make one conversation
+            Command::Say { speaker, text } => {
+                let mut says = vec![(speaker.clone(), text.clone())];
+                while let Some(Command::Say { speaker, text }) = script.commands.get(run.next) {
+                    says.push((speaker.clone(), text.clone()));
+                    run.next += 1;
+                }
+                actions.push(Action::Say(says));
+                run.wait = Wait::Dialog;
+                return false;
+            }
+            Command::Set(flag) => {
+                flags.insert(flag.clone());
+            }
+            Command::Clear(flag) => {
+                flags.remove(flag);
+            }
+            Command::If { flag, set, end } => {
+                if flags.contains(flag) != *set {
+                    run.next = *end;
+                }
+            }
+            Command::Wait(time) => {
+                run.wait = Wait::Time(*time);
+                return false;
+            }
+            Command::Do(action) => {
+                actions.push(action.clone());
+                if let Action::Camera { time, .. } = action {
+                    run.wait = Wait::Time(*time);
+                    return false;
+                }
+            }
+        }
+    }
+
+    true
+}
+
+fn read(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|_| {
+        eprintln!("No script file: {}", path);
+        String::new()
+    })
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    // Notify texts left for the game, taken as it would
+    fn notes(scripts: &mut Scripts) -> Vec<String> {
+        scripts
+            .actions
+            .drain(..)
+            .filter_map(|action| match action {
+                Action::Notify(text) => Some(text),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn if_and_if_not_jump_past_their_end() {
+        let source = "
+            on enter 001
+            if door
+            notify open
+            end
+            if not door
+            notify shut
+            end
+            end
+        ";
+        let mut scripts = Scripts::from_source(source);
+
+        scripts.update(0.1, "001", &mut flags(&[]), false);
+        assert_eq!(notes(&mut scripts), ["shut"]);
+
+        scripts.update(0.1, "002", &mut flags(&[]), false);
+        scripts.update(0.1, "001", &mut flags(&["door"]), false);
+        assert_eq!(notes(&mut scripts), ["open"]);
+    }
+
+    #[test]
+    fn once_scripts_run_a_single_time() {
+        let mut scripts = Scripts::from_source("on enter 001, once\nnotify hello\nend");
+        let mut flags = flags(&[]);
+
+        for room in ["001", "002", "001"] {
+            scripts.update(0.1, room, &mut flags, false);
+        }
+        assert_eq!(notes(&mut scripts), ["hello"]);
+        assert_eq!(scripts.fired(), ["on enter 001, once"]);
+    }
+
+    #[test]
+    fn wait_holds_the_script_for_its_time() {
+        let mut scripts = Scripts::from_source("on enter 001\nwait 1\nnotify later\nend");
+        let mut flags = flags(&[]);
+
+        // Counted from the frame after the one that reached it
+        scripts.update(0.1, "001", &mut flags, false);
+        scripts.update(0.5, "001", &mut flags, false);
+        scripts.update(0.4, "001", &mut flags, false);
+        assert!(notes(&mut scripts).is_empty());
+
+        scripts.update(0.2, "001", &mut flags, false);
+        assert_eq!(notes(&mut scripts), ["later"]);
+    }
+
+    #[test]
+    fn flags_start_scripts_only_as_they_go_up() {
+        let mut scripts = Scripts::from_source("on flag alarm\nnotify alarm\nend");
+        let mut up = flags(&["alarm"]);
+        let mut down = flags(&[]);
+
+        scripts.update(0.1, "001", &mut up, false);
+        scripts.update(0.1, "001", &mut up, false);
+        assert_eq!(notes(&mut scripts).len(), 1);
+
+        scripts.update(0.1, "001", &mut down, false);
+        scripts.update(0.1, "001", &mut up, false);
+        assert_eq!(notes(&mut scripts).len(), 1);
+    }
+
+    #[test]
+    fn rescue_and_defeat_go_by_the_game_flags() {
+        let source = "
+            on rescue Kessler
+            notify free
+            end
+            on defeat Shotgunner
+            notify down
+            end
+        ";
+        let mut scripts = Scripts::from_source(source);
+
+        scripts.update(0.1, "001", &mut flags(&["rescued Kessler"]), false);
+        assert_eq!(notes(&mut scripts), ["free"]);
+
+        scripts.update(0.1, "001", &mut flags(&["defeated shotgunner"]), false);
+        assert_eq!(notes(&mut scripts), ["down"]);
+    }
+
+    #[test]
+    fn say_waits_while_the_codec_is_busy() {
+        let source = "
+            on enter 001
+            say Diane; One
+            say Snake; Two
+            notify after
+            end
+        ";
+        let mut scripts = Scripts::from_source(source);
+        let mut flags = flags(&[]);
+
+        scripts.update(0.1, "001", &mut flags, false);
+        match scripts.actions.as_slice() {
+            [Action::Say(says)] => assert_eq!(says.len(), 2),
+            actions => panic!("expected one conversation, got {:?}", actions),
+        }
+        scripts.actions.clear();
+
+        scripts.update(0.1, "001", &mut flags, true);
+        assert!(notes(&mut scripts).is_empty());
+
+        scripts.update(0.1, "001", &mut flags, false);
+        assert_eq!(notes(&mut scripts), ["after"]);
+    }
+
+    #[test]
+    fn reload_drops_running_scripts() {
+        let path = std::env::temp_dir().join(format!("scripts_{}.txt", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        fs::write(&path, "on enter 001\nwait 10\nnotify late\nend\n").unwrap();
+
+        let mut scripts = Scripts::new(&path);
+        let mut flags = flags(&[]);
+        scripts.update(0.1, "001", &mut flags, false);
+        assert_eq!(scripts.running.len(), 1);
+
+        // As if the file had been saved since
+        scripts.modified = None;
+        scripts.update(RELOAD_INTERVAL, "001", &mut flags, false);
+        fs::remove_file(&path).unwrap();
+
+        assert!(scripts.running.is_empty());
+        assert_eq!(scripts.scripts.len(), 1);
+    }
+}
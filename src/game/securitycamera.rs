@@ -0,0 +1,108 @@
+use macroquad::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::game::ColliderMap;
+use crate::game::Settings;
+use crate::game::Vision;
+
+use super::player::Direction;
+
+#[derive(Debug, Clone)]
+pub struct SecurityCamera {
+    settings: Settings,
+    texture: Texture2D,
+    base: Vec2,
+    timer: f32,
+    pub room: String,
+    pub facing: Vec2,
+    pub bounds: Rect,
+}
+
+// Frame Size
+const FS_CAMERA: Vec2 = Vec2::new(16.0, 16.0);
+
+// Conts
+const SCALE: f32 = 4.0;
+const SWEEP_ANGLE: f32 = 0.7;
+const SWEEP_TIME: f32 = 6.0;
+const VISION: Vision = Vision::new(352.0, 0.3);
+
+impl SecurityCamera {
+    pub async fn new(settings: Settings, position: Vec2, facing: Direction, room: &str) -> Self {
+        // Load Textures
+        let texture = load_texture("assets/enemies/cameras.png").await.unwrap();
+        texture.set_filter(FilterMode::Nearest);
+
+        let size = FS_CAMERA * SCALE * 0.8;
+
+        Self {
+            settings,
+            texture,
+            base: facing.vector(),
+            timer: 0.0,
+            room: room.to_string(),
+            facing: facing.vector(),
+            bounds: Rect::new(position.x, position.y, size.x, size.y),
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.settings.update();
+
+        // Swing back and forth around the mounted direction
+        self.timer = (self.timer + delta_time) % SWEEP_TIME;
+        let angle = SWEEP_ANGLE * (self.timer / SWEEP_TIME * TAU).sin();
+        self.facing = Vec2::from_angle(angle).rotate(self.base);
+    }
+
+    // Visibility cuts its range the same as a guard's, a box gets closer
+    pub fn sees(&self, target: Vec2, visibility: f32, collider_map: &ColliderMap) -> bool {
+        let eye = self.bounds.center();
+        eye.distance(target) <= VISION.range * visibility
+            && VISION.sees(eye, self.facing, target, collider_map)
+    }
+
+    pub fn draw(&self) {
+        VISION.draw(
+            self.bounds.center(),
+            self.facing,
+            Color::new(1.0, 1.0, 0.6, 0.15),
+        );
+
+        let column = match Direction::from_vector(self.facing) {
+            Direction::Down => 0.0,
+            Direction::Left => 1.0,
+            Direction::Up => 2.0,
+            Direction::Right => 3.0,
+        };
+        let src_rect = Rect::new(
+            1.0 + (FS_CAMERA.x + 1.0) * column,
+            1.0,
+            FS_CAMERA.x,
+            FS_CAMERA.y,
+        );
+
+        if self.settings.debug {
+            draw_rectangle_lines(
+                self.bounds.x,
+                self.bounds.y,
+                self.bounds.w,
+                self.bounds.h,
+                2.0,
+                YELLOW,
+            );
+        }
+
+        draw_texture_ex(
+            &self.texture,
+            self.bounds.x,
+            self.bounds.y,
+            WHITE,
+            DrawTextureParams {
+                source: Some(src_rect),
+                dest_size: Some(self.bounds.size()),
+                ..Default::default()
+            },
+        );
+    }
+}
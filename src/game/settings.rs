@@ -4,11 +4,16 @@ use macroquad::prelude::*;
 pub struct Settings {
     pub debug: bool,
     pub zoom: bool,
+    // 0 to 1
+    pub music_volume: f32,
+    pub sound_volume: f32,
 }
 
+const VOLUME_STEP: f32 = 0.1;
+
 impl Settings {
     pub fn new() -> Self {
-        Self { debug: true, zoom: false }
+        Self { debug: true, zoom: false, music_volume: 0.6, sound_volume: 1.0 }
     }
 
     pub fn toggle_debug(&mut self) {
@@ -19,6 +24,10 @@ impl Settings {
         self.zoom = !self.zoom;
     }
 
+    fn step_volume(volume: f32, step: f32) -> f32 {
+        (volume + step).clamp(0.0, 1.0)
+    }
+
     pub fn update(&mut self) {
         if is_key_pressed(KeyCode::Space) {
             self.toggle_debug();
@@ -26,5 +35,18 @@ impl Settings {
         if is_key_pressed(KeyCode::Z) {
             self.toggle_zoom();
         }
+        // [ and ] for music, - and = for sounds
+        if is_key_pressed(KeyCode::LeftBracket) {
+            self.music_volume = Settings::step_volume(self.music_volume, -VOLUME_STEP);
+        }
+        if is_key_pressed(KeyCode::RightBracket) {
+            self.music_volume = Settings::step_volume(self.music_volume, VOLUME_STEP);
+        }
+        if is_key_pressed(KeyCode::Minus) {
+            self.sound_volume = Settings::step_volume(self.sound_volume, -VOLUME_STEP);
+        }
+        if is_key_pressed(KeyCode::Equal) {
+            self.sound_volume = Settings::step_volume(self.sound_volume, VOLUME_STEP);
+        }
     }
 }
\ No newline at end of file
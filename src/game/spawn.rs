@@ -0,0 +1,146 @@
+use macroquad::prelude::*;
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+// Spawn files sit beside the room arrays, one entity per line:
+// room, kind, cell x, cell y[, args...]
+// Cells are the same 32px grid the collider maps use.
+#[derive(Debug, Clone)]
+pub struct Spawn {
+    pub room: String,
+    pub kind: SpawnKind,
+    pub cell: Vec2,
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpawnKind {
+    Guard,
+    Dog,
+    Camera,
+    Laser,
+    Floor,
+    Gas,
+    Dark,
+    Boss,
+    Pickup,
+    Mine,
+    Prisoner,
+    Respawn,
+}
+
+pub const CELL_SIZE: f32 = 32.0;
+
+// Where something was placed is enough to tell it apart, room states and
+// saves go by it
+pub fn id(room: &str, position: Vec2) -> String {
+    format!("{}:{}:{}", room, position.x, position.y)
+}
+
+impl SpawnKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "guard" => Some(SpawnKind::Guard),
+            "dog" => Some(SpawnKind::Dog),
+            "camera" => Some(SpawnKind::Camera),
+            "laser" => Some(SpawnKind::Laser),
+            "floor" => Some(SpawnKind::Floor),
+            "gas" => Some(SpawnKind::Gas),
+            "dark" => Some(SpawnKind::Dark),
+            "boss" => Some(SpawnKind::Boss),
+            "pickup" => Some(SpawnKind::Pickup),
+            "mine" => Some(SpawnKind::Mine),
+            "prisoner" => Some(SpawnKind::Prisoner),
+            "respawn" => Some(SpawnKind::Respawn),
+            _ => None,
+        }
+    }
+}
+
+impl Spawn {
+    pub fn load(spawn_file: &str) -> Vec<Spawn> {
+        let mut spawns = Vec::new();
+
+        let file = match File::open(spawn_file) {
+            Ok(file) => file,
+            Err(_) => {
+                eprintln!("No spawn file: {}", spawn_file);
+                return spawns;
+            }
+        };
+        let reader = BufReader::new(file);
+
+        for line in reader.lines() {
+            let line = line.unwrap();
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(spawn) = Spawn::parse(line) {
+                spawns.push(spawn);
+            }
+        }
+
+        spawns
+    }
+
+    // One spawn line, scripts spawn with the same ones
+    pub fn parse(line: &str) -> Option<Spawn> {
+        let fields: Vec<String> = line.split(",").map(|s| s.trim().to_owned()).collect();
+        if fields.len() < 4 {
+            eprintln!("Bad spawn: {}", line);
+            return None;
+        }
+
+        let kind = match SpawnKind::from_name(&fields[1]) {
+            Some(kind) => kind,
+            None => {
+                eprintln!("Unknown spawn kind: {}", fields[1]);
+                return None;
+            }
+        };
+
+        let (x, y) = match (fields[2].parse::<f32>(), fields[3].parse::<f32>()) {
+            (Ok(x), Ok(y)) => (x, y),
+            _ => {
+                eprintln!("Bad spawn cell: {}", line);
+                return None;
+            }
+        };
+
+        Some(Spawn {
+            room: fields[0].clone(),
+            kind,
+            cell: Vec2::new(x, y),
+            args: fields[4..].to_vec(),
+        })
+    }
+
+    // World position of the top left of the spawn cell
+    pub fn world_position(&self, room_bounds: Rect) -> Vec2 {
+        Vec2::new(
+            room_bounds.x + self.cell.x * CELL_SIZE,
+            room_bounds.y + self.cell.y * CELL_SIZE,
+        )
+    }
+
+    // Cell area starting at the spawn, sized by the first two args
+    pub fn area(&self, room_bounds: Rect) -> Rect {
+        let size = |index: usize| {
+            self.args
+                .get(index)
+                .and_then(|arg| arg.parse::<f32>().ok())
+                .unwrap_or(1.0)
+        };
+
+        let position = self.world_position(room_bounds);
+        Rect::new(
+            position.x,
+            position.y,
+            size(0) * CELL_SIZE,
+            size(1) * CELL_SIZE,
+        )
+    }
+}
@@ -0,0 +1,125 @@
+use macroquad::prelude::*;
+
+use crate::game::{Anchor, Ui};
+
+// Where the game is at. The top of the stack gets the input, the ones
+// under it stay put until it's popped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum State {
+    Title,
+    Play,
+    Pause,
+    Codec,
+    Menu,
+    GameOver,
+    Continue,
+    // Slot lists and the password entry, over the title or the pause menu
+    Save,
+    Load,
+    Password,
+}
+
+#[derive(Debug, Clone)]
+pub struct StateStack {
+    states: Vec<State>,
+    // Time in the top state and its highlighted option
+    timer: f32,
+    cursor: usize,
+}
+
+// Layout
+const TITLE_SIZE: f32 = 96.0;
+const FONT_SIZE: f32 = 32.0;
+const LINE_HEIGHT: f32 = 48.0;
+const NOTE_OFFSET: f32 = 96.0;
+const SHADE: Color = Color::new(0.0, 0.0, 0.0, 0.7);
+
+impl StateStack {
+    pub fn new(first: State) -> Self {
+        Self {
+            states: vec![first],
+            timer: 0.0,
+            cursor: 0,
+        }
+    }
+
+    pub fn top(&self) -> State {
+        *self.states.last().unwrap()
+    }
+
+    // What the stack started from, the title or the game
+    pub fn bottom(&self) -> State {
+        self.states[0]
+    }
+
+    pub fn timer(&self) -> f32 {
+        self.timer
+    }
+
+    pub fn push(&mut self, state: State) {
+        self.states.push(state);
+        self.entered();
+    }
+
+    // The bottom state stays, there's always somewhere to be
+    pub fn pop(&mut self) {
+        if self.states.len() > 1 {
+            self.states.pop();
+        }
+        self.entered();
+    }
+
+    // Swaps out the top state
+    pub fn replace(&mut self, state: State) {
+        self.states.pop();
+        self.push(state);
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.timer += delta_time;
+    }
+
+    // Up and down through the options, the one picked on Enter
+    pub fn choose(&mut self, options: usize) -> Option<usize> {
+        if is_key_pressed(KeyCode::Up) {
+            self.cursor = (self.cursor + options - 1) % options;
+        }
+        if is_key_pressed(KeyCode::Down) {
+            self.cursor = (self.cursor + 1) % options;
+        }
+
+        is_key_pressed(KeyCode::Enter).then_some(self.cursor)
+    }
+
+    // A heading with the options under it, shading whatever's behind
+    pub fn draw_screen(&self, ui: &Ui, heading: &str, color: Color, options: &[&str]) {
+        let screen = ui.size();
+        draw_rectangle(0.0, 0.0, ui.px(screen.x), ui.px(screen.y), SHADE);
+
+        // Heading a fifth of the screen above the middle
+        let top = ui.percent(0.0, -20.0);
+        ui.label(heading, Anchor::Center, top, TITLE_SIZE, color);
+
+        for (index, option) in options.iter().enumerate() {
+            let (text, color) = if index == self.cursor {
+                (format!("> {} <", option), YELLOW)
+            } else {
+                (option.to_string(), WHITE)
+            };
+            let offset = top + vec2(0.0, TITLE_SIZE + LINE_HEIGHT * index as f32);
+            ui.label(&text, Anchor::Center, offset, FONT_SIZE, color);
+        }
+    }
+
+    // A line near the bottom, under whatever options there are
+    pub fn draw_note(&self, ui: &Ui, text: &str, color: Color) {
+        let offset = vec2(0.0, NOTE_OFFSET - FONT_SIZE);
+        ui.label(text, Anchor::Bottom, offset, FONT_SIZE, color);
+    }
+
+    // Private
+    fn entered(&mut self) {
+        self.timer = 0.0;
+        self.cursor = 0;
+    }
+}
@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use crate::game::Drain;
+
+// Timed conditions on Snake. Items and hazards apply them, the list
+// takes care of ticking, stacking and running out. Time only passes
+// while it's updated, so they hold in menus.
+#[derive(Debug, Clone, Default)]
+pub struct StatusEffects {
+    statuses: Vec<Status>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusKind {
+    Poisoned,
+    Stunned,
+    Smoking,
+    Gassed,
+    Disguised,
+    Armored,
+}
+
+// What applying a status that's already there does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stacking {
+    // Starts the clock over
+    Refresh,
+    // Adds to the time left, up to max
+    Extend { max: Duration },
+    // Each dose adds to the tick, up to max doses
+    Stack { max: u32 },
+}
+
+#[derive(Debug, Clone)]
+struct Status {
+    kind: StatusKind,
+    left: Duration,
+    stacks: u32,
+    drain: Option<Drain>,
+}
+
+struct Tick {
+    interval: Duration,
+    amount: f32,
+    floor: f32,
+}
+
+struct Spec {
+    tick: Option<Tick>,
+    stacking: Stacking,
+    damage_scale: f32,
+    speed_scale: f32,
+    overlay: bool,
+}
+
+// For statuses kept up by something every frame, gone soon after it stops
+pub const HELD: Duration = Duration::from_millis(100);
+
+impl StatusKind {
+    fn spec(&self) -> Spec {
+        match self {
+            StatusKind::Poisoned => Spec {
+                tick: Some(Tick {
+                    interval: Duration::from_millis(1500),
+                    amount: 1.0,
+                    floor: 0.0,
+                }),
+                stacking: Stacking::Stack { max: 3 },
+                damage_scale: 1.0,
+                speed_scale: 1.0,
+                overlay: false,
+            },
+            StatusKind::Stunned => Spec {
+                tick: None,
+                stacking: Stacking::Extend {
+                    max: Duration::from_secs(3),
+                },
+                damage_scale: 1.0,
+                speed_scale: 0.0,
+                overlay: true,
+            },
+            StatusKind::Smoking => Spec {
+                tick: Some(Tick {
+                    interval: Duration::from_millis(1000),
+                    amount: 1.0,
+                    floor: 1.0,
+                }),
+                stacking: Stacking::Refresh,
+                damage_scale: 1.0,
+                speed_scale: 1.0,
+                overlay: true,
+            },
+            StatusKind::Gassed => Spec {
+                tick: Some(Tick {
+                    interval: Duration::from_millis(500),
+                    amount: 2.0,
+                    floor: 0.0,
+                }),
+                stacking: Stacking::Refresh,
+                damage_scale: 1.0,
+                speed_scale: 1.0,
+                overlay: false,
+            },
+            StatusKind::Disguised => Spec {
+                tick: None,
+                stacking: Stacking::Refresh,
+                damage_scale: 1.0,
+                speed_scale: 1.0,
+                overlay: false,
+            },
+            StatusKind::Armored => Spec {
+                tick: None,
+                stacking: Stacking::Refresh,
+                damage_scale: 0.5,
+                speed_scale: 1.0,
+                overlay: false,
+            },
+        }
+    }
+}
+
+impl StatusEffects {
+    pub fn new() -> Self {
+        Self {
+            statuses: Vec::new(),
+        }
+    }
+
+    pub fn apply(&mut self, kind: StatusKind, duration: Duration) {
+        let spec = kind.spec();
+
+        let status = match self.statuses.iter_mut().find(|status| status.kind == kind) {
+            Some(status) => status,
+            None => {
+                let drain = spec
+                    .tick
+                    .map(|tick| Drain::new(tick.interval, tick.amount, tick.floor));
+                self.statuses.push(Status {
+                    kind,
+                    left: duration,
+                    stacks: 1,
+                    drain,
+                });
+                return;
+            }
+        };
+
+        match spec.stacking {
+            Stacking::Refresh => status.left = status.left.max(duration),
+            Stacking::Extend { max } => status.left = (status.left + duration).min(max),
+            Stacking::Stack { max } => {
+                status.stacks = (status.stacks + 1).min(max);
+                status.left = status.left.max(duration);
+                if let (Some(drain), Some(tick)) = (&mut status.drain, spec.tick) {
+                    drain.set_amount(tick.amount * status.stacks as f32);
+                }
+            }
+        }
+    }
+
+    pub fn remove(&mut self, kind: StatusKind) {
+        self.statuses.retain(|status| status.kind != kind);
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.statuses.iter().any(|status| status.kind == kind)
+    }
+
+    pub fn update(&mut self, delta_time: f32, health: &mut f32) {
+        let step = Duration::from_secs_f32(delta_time);
+        for status in self.statuses.iter_mut() {
+            status.left = status.left.saturating_sub(step);
+        }
+        self.statuses.retain(|status| !status.left.is_zero());
+
+        for status in self.statuses.iter_mut() {
+            if let Some(drain) = &mut status.drain {
+                drain.tick(delta_time, health);
+            }
+        }
+    }
+
+    // How much of each hit gets through
+    pub fn damage_scale(&self) -> f32 {
+        self.statuses
+            .iter()
+            .map(|status| status.kind.spec().damage_scale)
+            .product()
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        self.statuses
+            .iter()
+            .map(|status| status.kind.spec().speed_scale)
+            .product()
+    }
+
+    // The first status with an animation gets drawn
+    pub fn overlay(&self) -> Option<StatusKind> {
+        self.statuses
+            .iter()
+            .map(|status| status.kind)
+            .find(|kind| kind.spec().overlay)
+    }
+}
@@ -0,0 +1,104 @@
+use macroquad::prelude::*;
+
+// Screen space layer for menus, the HUD, dialogs and debug overlays.
+// Layout is in pixels at the default window size, anchored to a point on
+// the screen and scaled with the window. The world camera never touches it.
+#[derive(Debug, Clone, Copy)]
+pub struct Ui {
+    scale: f32,
+    screen: Vec2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+// Layout size, the default window
+const BASE: Vec2 = Vec2::new(1280.0, 720.0);
+
+impl Anchor {
+    // Where the anchor sits across a box, 0 to 1 each way
+    fn factor(&self) -> Vec2 {
+        match self {
+            Anchor::TopLeft => vec2(0.0, 0.0),
+            Anchor::Top => vec2(0.5, 0.0),
+            Anchor::TopRight => vec2(1.0, 0.0),
+            Anchor::Left => vec2(0.0, 0.5),
+            Anchor::Center => vec2(0.5, 0.5),
+            Anchor::Right => vec2(1.0, 0.5),
+            Anchor::BottomLeft => vec2(0.0, 1.0),
+            Anchor::Bottom => vec2(0.5, 1.0),
+            Anchor::BottomRight => vec2(1.0, 1.0),
+        }
+    }
+
+    // Offsets push in from whichever edges the anchor is on
+    fn inward(&self) -> Vec2 {
+        let factor = self.factor();
+        vec2(
+            if factor.x > 0.5 { -1.0 } else { 1.0 },
+            if factor.y > 0.5 { -1.0 } else { 1.0 },
+        )
+    }
+}
+
+impl Ui {
+    // Switches to the default camera, everything drawn after is in screen pixels
+    pub fn begin() -> Self {
+        set_default_camera();
+
+        let screen = vec2(screen_width(), screen_height());
+        Self {
+            scale: (screen.x / BASE.x).min(screen.y / BASE.y),
+            screen,
+        }
+    }
+
+    // Layout units to screen pixels
+    pub fn px(&self, value: f32) -> f32 {
+        value * self.scale
+    }
+
+    // The screen in layout units
+    pub fn size(&self) -> Vec2 {
+        self.screen / self.scale
+    }
+
+    // Offset as a percentage of the screen, in layout units
+    pub fn percent(&self, x: f32, y: f32) -> Vec2 {
+        self.size() * vec2(x, y) / 100.0
+    }
+
+    // A box lined up by its own anchor point with the screen's, in screen pixels
+    pub fn rect(&self, anchor: Anchor, offset: Vec2, size: Vec2) -> Rect {
+        let factor = anchor.factor();
+        let size = size * self.scale;
+        let origin = (self.screen - size) * factor + offset * anchor.inward() * self.scale;
+        Rect::new(origin.x, origin.y, size.x, size.y)
+    }
+
+    // Like draw_text, position is the baseline in screen pixels
+    pub fn text(&self, text: &str, position: Vec2, font_size: f32, color: Color) {
+        draw_text(text, position.x, position.y, self.px(font_size), color);
+    }
+
+    // A line of text lined up like a box of its size
+    pub fn label(&self, text: &str, anchor: Anchor, offset: Vec2, font_size: f32, color: Color) {
+        let width = self.text_width(text, font_size) / self.scale;
+        let line = self.rect(anchor, offset, vec2(width, font_size));
+        self.text(text, vec2(line.x, line.bottom()), font_size, color);
+    }
+
+    pub fn text_width(&self, text: &str, font_size: f32) -> f32 {
+        measure_text(text, None, self.px(font_size) as u16, 1.0).width
+    }
+}
@@ -0,0 +1,36 @@
+use macroquad::prelude::*;
+
+use crate::game::ColliderMap;
+
+// A cone of sight, blocked by walls
+#[derive(Debug, Clone, Copy)]
+pub struct Vision {
+    pub range: f32,
+    pub half_angle: f32,
+}
+
+impl Vision {
+    pub const fn new(range: f32, half_angle: f32) -> Self {
+        Self { range, half_angle }
+    }
+
+    pub fn sees(&self, eye: Vec2, facing: Vec2, target: Vec2, collider_map: &ColliderMap) -> bool {
+        let to = target - eye;
+        let distance = to.length();
+        if distance > self.range {
+            return false;
+        }
+        if distance > 0.0 && facing.angle_between(to).abs() > self.half_angle {
+            return false;
+        }
+
+        collider_map.walls_between(eye, target) == 0
+    }
+
+    pub fn draw(&self, eye: Vec2, facing: Vec2, color: Color) {
+        let left = Vec2::from_angle(-self.half_angle).rotate(facing) * self.range;
+        let right = Vec2::from_angle(self.half_angle).rotate(facing) * self.range;
+
+        draw_triangle(eye, eye + left, eye + right, color);
+    }
+}
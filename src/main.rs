@@ -20,11 +20,10 @@ async fn main() {
     let mut game = Game::new().await.unwrap();
 
     loop {
-        if is_key_down(KeyCode::Escape) {
-            std::process::exit(0);
-        }
-
         game.update().await;
+        if !game.is_running() {
+            break;
+        }
 
         clear_background(PINK);
         game.draw();